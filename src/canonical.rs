@@ -0,0 +1,224 @@
+use eyre::{eyre, Result, WrapErr};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::loader::{
+    find_resource, resource_files, visit_pools, PoolVisitor, RawPool, ResourceFormat,
+    ResourcePoolKind, COMMENT_KEY, EXTENDS_KEY, REMOVE_KEY, TEMPLATES_KEY,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormattedFile {
+    pub path: PathBuf,
+    pub changed: bool,
+    pub content: String,
+}
+
+/// Canonical layout of every JSON pool file under `dir`: entries sorted by id, fields in
+/// loader struct order after `__comment__`, 4-space indentation. YAML and TOML files are
+/// left as they are
+pub fn format_resources(dir: &Path) -> Result<Vec<FormattedFile>> {
+    let mut visitor = FormatVisitor { dir, files: vec![] };
+    visit_pools(&mut visitor)?;
+    Ok(visitor.files)
+}
+
+struct FormatVisitor<'a> {
+    dir: &'a Path,
+    files: Vec<FormattedFile>,
+}
+
+impl PoolVisitor for FormatVisitor<'_> {
+    type Error = eyre::Report;
+
+    fn visit<P: ResourcePoolKind>(&mut self) -> Result<()> {
+        let path = find_resource(self.dir, P::FILE_NAME)?;
+        let files = format_pool::<P::Entry>(&path, &[P::FILE_NAME, P::FIELD_NAME])
+            .wrap_err_with(|| format!("format {} pool", P::FILE_NAME))?;
+        self.files.extend(files);
+        Ok(())
+    }
+}
+
+fn format_pool<E: DeserializeOwned + Serialize>(
+    path: &Path,
+    keys: &[&str],
+) -> Result<Vec<FormattedFile>> {
+    let raw_pool = RawPool::parse(path, keys)?;
+    let resolver = raw_pool.resolver();
+    let canonical_entry = |raw: &Value| -> Result<Value> {
+        // the loader ignores unknown fields, a misspelled one would silently fall back to
+        // its default, so formatting refuses them
+        let mut unknown = vec![];
+        let entry: E = serde_ignored::deserialize(resolver.resolve(raw)?, |path| {
+            let path = path.to_string();
+            if !path.ends_with(COMMENT_KEY) {
+                unknown.push(path);
+            }
+        })?;
+        if !unknown.is_empty() {
+            return Err(eyre!(
+                "entry {} has unknown fields: {}",
+                raw.get("id").unwrap_or(&Value::Null),
+                unknown.join(", ")
+            ));
+        }
+        Ok(canonical_value(raw, &serde_json::to_value(entry)?))
+    };
+    let mut files = vec![];
+    for file in resource_files(path)? {
+        if ResourceFormat::from_path(&file)? != ResourceFormat::Json {
+            continue;
+        }
+        let original = fs::read_to_string(&file)?;
+        let formatted = match serde_json::from_str(&original)? {
+            Value::Object(document) if !document.contains_key("id") => {
+                // older pool keys are renamed to the current one
+                let mut document = document
+                    .into_iter()
+                    .map(|(k, v)| {
+                        if keys.contains(&k.as_str()) {
+                            (keys[0].to_owned(), v)
+                        } else {
+                            (k, v)
+                        }
+                    })
+                    .collect::<Map<_, _>>();
+                for (key, list) in document.iter_mut() {
+                    let Value::Array(list) = list else {
+                        continue;
+                    };
+                    if key == TEMPLATES_KEY || key == REMOVE_KEY {
+                        continue;
+                    }
+                    let mut entries = list
+                        .iter()
+                        .map(canonical_entry)
+                        .collect::<Result<Vec<_>>>()
+                        .wrap_err_with(|| format!("format {}", file.display()))?;
+                    entries.sort_by_key(|v| v.get("id").and_then(Value::as_u64));
+                    *list = entries;
+                }
+                Value::Object(document)
+            }
+            entry => {
+                canonical_entry(&entry).wrap_err_with(|| format!("format {}", file.display()))?
+            }
+        };
+        let content = to_pretty_json(&formatted)?;
+        files.push(FormattedFile {
+            changed: content != original,
+            path: file,
+            content,
+        });
+    }
+    Ok(files)
+}
+
+/// Lays `raw` out in the field order of its typed form, taking the normalized values from
+/// `typed` but only for the fields `raw` spells out, so defaults and inherited fields stay
+/// implicit
+fn canonical_value(raw: &Value, typed: &Value) -> Value {
+    match (raw, typed) {
+        (Value::Object(raw_fields), Value::Object(typed_fields)) => {
+            let mut fields = Map::new();
+            if let Some(comment) = raw_fields.get(COMMENT_KEY) {
+                fields.insert(COMMENT_KEY.to_owned(), comment.clone());
+            }
+            for (key, typed_field) in typed_fields {
+                let Some(raw_field) = raw_fields.get(key) else {
+                    continue;
+                };
+                fields.insert(key.clone(), canonical_value(raw_field, typed_field));
+                if let (Some(parent), "id") = (raw_fields.get(EXTENDS_KEY), key.as_str()) {
+                    fields.insert(EXTENDS_KEY.to_owned(), parent.clone());
+                }
+            }
+            // alias spellings of known fields stay at the end
+            for (key, raw_field) in raw_fields {
+                if !fields.contains_key(key) {
+                    fields.insert(key.clone(), raw_field.clone());
+                }
+            }
+            Value::Object(fields)
+        }
+        (Value::Array(raw_list), Value::Array(typed_list))
+            if raw_list.len() == typed_list.len() =>
+        {
+            let list = raw_list.iter().zip(typed_list);
+            Value::Array(
+                list.map(|(raw, typed)| canonical_value(raw, typed))
+                    .collect(),
+            )
+        }
+        // code enums may be written by number or by name
+        (Value::Number(_), Value::String(_)) => raw.clone(),
+        (_, typed) => typed.clone(),
+    }
+}
+
+pub fn to_pretty_json<T: Serialize>(value: &T) -> Result<String> {
+    let mut content = vec![];
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
+    value.serialize(&mut serde_json::Serializer::with_formatter(
+        &mut content,
+        formatter,
+    ))?;
+    Ok(String::from_utf8(content)? + "\n")
+}
+
+#[test]
+fn test_format_resources() {
+    use crate::loader::RESOURCE_FILES;
+
+    let dir = std::env::temp_dir().join("spore-warriors-format");
+    std::fs::create_dir_all(&dir).unwrap();
+    for name in RESOURCE_FILES {
+        let file = format!("{name}.json");
+        std::fs::copy(Path::new("./resources").join(&file), dir.join(&file)).unwrap();
+    }
+    std::fs::write(
+        dir.join("actions.json"),
+        r#"{"actions": [
+            {"system_pool": [1, 3], "random": false, "id": 11},
+            {"id": 10, "__comment__": "basic", "system_pool": [1, 2, 3, 4], "random": true}
+        ]}"#,
+    )
+    .unwrap();
+    let files = format_resources(&dir).unwrap();
+    assert_eq!(files.len(), RESOURCE_FILES.len());
+    assert!(files[0].changed);
+    let actions: Value = serde_json::from_str(&files[0].content).unwrap();
+    assert_eq!(
+        actions["actions"][0]
+            .as_object()
+            .unwrap()
+            .keys()
+            .collect::<Vec<_>>(),
+        [COMMENT_KEY, "id", "random", "system_pool"]
+    );
+    assert_eq!(actions["actions"][1]["id"], 11);
+
+    for file in &files {
+        std::fs::write(&file.path, &file.content).unwrap();
+    }
+    let formatted = crate::loader::ResourceSet::load(&dir).unwrap();
+    let reformatted = format_resources(&dir).unwrap();
+    assert!(reformatted.iter().all(|v| !v.changed));
+    let expected = crate::loader::ResourceSet::load("./resources".as_ref()).unwrap();
+    assert_eq!(formatted.scene_pool, expected.scene_pool);
+
+    std::fs::write(
+        dir.join("actions.json"),
+        r#"{"actions": [{"id": 10, "random": true, "system_pool": [1], "randm": false}]}"#,
+    )
+    .unwrap();
+    let err = format_resources(&dir).unwrap_err();
+    assert!(
+        format!("{err:#}").contains("entry 10 has unknown fields: randm"),
+        "{err:#}"
+    );
+}
@@ -0,0 +1,252 @@
+use eyre::Result;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::fmt;
+
+use crate::loader::{visit_pools, PoolVisitor, ResourcePoolKind, ResourceSet};
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FieldChange {
+    Modified {
+        field: String,
+        old: Value,
+        new: Value,
+    },
+    Members {
+        field: String,
+        added: Vec<Value>,
+        removed: Vec<Value>,
+    },
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct EntryDiff {
+    pub id: u16,
+    pub changes: Vec<FieldChange>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct PoolDiff {
+    pub pool: String,
+    pub added: Vec<u16>,
+    pub removed: Vec<u16>,
+    pub changed: Vec<EntryDiff>,
+}
+
+impl PoolDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Default)]
+pub struct ResourceDiff {
+    pub pools: Vec<PoolDiff>,
+}
+
+impl ResourceDiff {
+    pub fn is_empty(&self) -> bool {
+        self.pools.is_empty()
+    }
+}
+
+struct DiffVisitor<'a> {
+    old: &'a ResourceSet,
+    new: &'a ResourceSet,
+    pools: Vec<PoolDiff>,
+}
+
+impl PoolVisitor for DiffVisitor<'_> {
+    type Error = Infallible;
+
+    fn visit<P: ResourcePoolKind>(&mut self) -> Result<(), Infallible> {
+        let pool = diff_pool::<P>(P::of(self.old), P::of(self.new));
+        if !pool.is_empty() {
+            self.pools.push(pool);
+        }
+        Ok(())
+    }
+}
+
+pub fn diff(old: &ResourceSet, new: &ResourceSet) -> ResourceDiff {
+    let mut visitor = DiffVisitor {
+        old,
+        new,
+        pools: vec![],
+    };
+    let Ok(()) = visit_pools(&mut visitor);
+    ResourceDiff {
+        pools: visitor.pools,
+    }
+}
+
+pub fn diff_pool<P: ResourcePoolKind>(old: &P, new: &P) -> PoolDiff {
+    let index = |pool: &P| -> BTreeMap<u16, Value> {
+        pool.entries()
+            .iter()
+            .map(|v| (P::id(v), serde_json::to_value(v).expect("serialize entry")))
+            .collect()
+    };
+    let old = index(old);
+    let new = index(new);
+    let added = new
+        .keys()
+        .filter(|v| !old.contains_key(v))
+        .cloned()
+        .collect();
+    let removed = old
+        .keys()
+        .filter(|v| !new.contains_key(v))
+        .cloned()
+        .collect();
+    let changed = old
+        .iter()
+        .filter_map(|(id, old_entry)| {
+            let new_entry = new.get(id)?;
+            let mut changes = vec![];
+            diff_value("", old_entry, new_entry, &mut changes);
+            (!changes.is_empty()).then_some(EntryDiff { id: *id, changes })
+        })
+        .collect();
+    PoolDiff {
+        pool: P::ENTRY_NAME.to_owned(),
+        added,
+        removed,
+        changed,
+    }
+}
+
+fn is_scalar_list(list: &[Value]) -> bool {
+    list.iter()
+        .all(|v| v.is_number() || v.is_string() || v.is_boolean())
+}
+
+fn join_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_owned()
+    } else {
+        format!("{path}.{key}")
+    }
+}
+
+fn diff_value(path: &str, old: &Value, new: &Value, changes: &mut Vec<FieldChange>) {
+    if old == new {
+        return;
+    }
+    match (old, new) {
+        (Value::Object(old_fields), Value::Object(new_fields)) => {
+            let keys = old_fields
+                .keys()
+                .chain(new_fields.keys().filter(|v| !old_fields.contains_key(*v)));
+            for key in keys {
+                let old_field = old_fields.get(key).unwrap_or(&Value::Null);
+                let new_field = new_fields.get(key).unwrap_or(&Value::Null);
+                diff_value(&join_path(path, key), old_field, new_field, changes);
+            }
+        }
+        (Value::Array(old_list), Value::Array(new_list))
+            if is_scalar_list(old_list) && is_scalar_list(new_list) =>
+        {
+            let mut counts = BTreeMap::<String, (Value, i32)>::new();
+            old_list.iter().for_each(|v| {
+                counts.entry(v.to_string()).or_insert((v.clone(), 0)).1 -= 1;
+            });
+            new_list.iter().for_each(|v| {
+                counts.entry(v.to_string()).or_insert((v.clone(), 0)).1 += 1;
+            });
+            let mut added = vec![];
+            let mut removed = vec![];
+            for (value, count) in counts.into_values() {
+                for _ in 0..count.abs() {
+                    if count > 0 {
+                        added.push(value.clone());
+                    } else {
+                        removed.push(value.clone());
+                    }
+                }
+            }
+            if added.is_empty() && removed.is_empty() {
+                changes.push(FieldChange::Modified {
+                    field: path.to_owned(),
+                    old: old.clone(),
+                    new: new.clone(),
+                });
+            } else {
+                changes.push(FieldChange::Members {
+                    field: path.to_owned(),
+                    added,
+                    removed,
+                });
+            }
+        }
+        (Value::Array(old_list), Value::Array(new_list)) if old_list.len() == new_list.len() => {
+            for (i, (old_item, new_item)) in old_list.iter().zip(new_list).enumerate() {
+                diff_value(&format!("{path}[{i}]"), old_item, new_item, changes);
+            }
+        }
+        _ => changes.push(FieldChange::Modified {
+            field: path.to_owned(),
+            old: old.clone(),
+            new: new.clone(),
+        }),
+    }
+}
+
+impl fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldChange::Modified { field, old, new } => write!(f, "{field} {old} → {new}"),
+            FieldChange::Members {
+                field,
+                added,
+                removed,
+            } => {
+                write!(f, "{field}")?;
+                added.iter().try_for_each(|v| write!(f, " +{v}"))?;
+                removed.iter().try_for_each(|v| write!(f, " -{v}"))
+            }
+        }
+    }
+}
+
+impl fmt::Display for ResourceDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for pool in &self.pools {
+            let name = &pool.pool;
+            for id in &pool.added {
+                writeln!(f, "{name} {id}: added")?;
+            }
+            for id in &pool.removed {
+                writeln!(f, "{name} {id}: removed")?;
+            }
+            for entry in &pool.changed {
+                let changes = entry
+                    .changes
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>();
+                writeln!(f, "{name} {}: {}", entry.id, changes.join(", "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_diff_resource_set() {
+    let old = ResourceSet::load("./resources".as_ref()).unwrap();
+    let mut new = old.clone();
+    let enemy = &mut new.enemy_pool.enemy_pool[1];
+    enemy.hp = 400;
+    enemy.loot_pool.push(2004);
+    let resource_diff = diff(&old, &new);
+    println!("[DIFF] {}", serde_json::to_string(&resource_diff).unwrap());
+    assert_eq!(
+        resource_diff.to_string(),
+        "Enemy 3001: hp 370 → 400, loot_pool +2004\n"
+    );
+    assert!(diff(&old, &old).is_empty());
+}
@@ -0,0 +1,320 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::loader::{
+    Action, Enemy, EnemyRank, LevelNode, NodeInstance, Random, ResourcePoolKind, ResourceSet,
+    ScenePartition, System, TargetType, Value,
+};
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct EnemyRating {
+    pub id: u16,
+    pub rank: EnemyRank,
+    /// `hp` plus the `armor` and `shield` absorbed before it
+    pub effective_hp: f64,
+    /// Mean damage dealt to the opponent per turn over the action strategy
+    pub damage_per_turn: f64,
+    /// Geometric mean of `effective_hp` and `damage_per_turn`
+    pub difficulty: f64,
+}
+
+/// Share by which a lower ranked enemy may rate above a higher ranked one before
+/// `difficulty --check` fails, the rating is a rough heuristic and close calls are noise
+pub const DEFAULT_TOLERANCE: f64 = 0.25;
+
+/// A lower ranked enemy, by the rank order given to `DifficultyReport::new`, rated above a
+/// higher ranked one
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct RankMismatch {
+    pub lower: EnemyRating,
+    pub higher: EnemyRating,
+}
+
+/// Difficulty of the enemy nodes found at one row of a scene
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct CurvePoint {
+    pub y: u8,
+    pub enemies: Vec<u16>,
+    pub difficulty: f64,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct SceneCurve {
+    pub scene: u16,
+    pub points: Vec<CurvePoint>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Default)]
+pub struct DifficultyReport {
+    pub enemies: Vec<EnemyRating>,
+    pub mismatches: Vec<RankMismatch>,
+    pub scenes: Vec<SceneCurve>,
+}
+
+pub fn random_mean<T: Copy + Into<f64>>(random: &Random<T>) -> f64 {
+    (random.min.into() + random.max.into()) / 2.0
+}
+
+/// Mean of `values`, 0 when there are none
+pub fn mean(values: impl IntoIterator<Item = f64>) -> f64 {
+    let (sum, count) = values
+        .into_iter()
+        .fold((0.0, 0), |(sum, count), v| (sum + v, count + 1));
+    if count == 0 {
+        0.0
+    } else {
+        sum / count as f64
+    }
+}
+
+/// Expected number of times each node of the partition pool is generated, `count` nodes
+/// are drawn uniformly from the pool
+pub fn node_pool_weight(partition: &ScenePartition) -> f64 {
+    if partition.node_pool.is_empty() {
+        return 0.0;
+    }
+    random_mean(&partition.count) / partition.node_pool.len() as f64
+}
+
+fn value_mean(value: &Value) -> f64 {
+    match value {
+        Value::Number(v) => *v as f64,
+        Value::Random(v) => random_mean(v),
+    }
+}
+
+/// Systems aimed at the opponent count their first argument, raised by `attack`, as damage
+fn system_damage(system: &System, attack: u8) -> f64 {
+    match (system.target_type, system.args.first()) {
+        (TargetType::Opponent, Some(amount)) => value_mean(amount) + attack as f64,
+        _ => 0.0,
+    }
+}
+
+/// A random action runs one of its systems, otherwise all of them
+fn action_damage(action: &Action, resource_set: &ResourceSet, attack: u8) -> f64 {
+    let damages = action
+        .system_pool
+        .iter()
+        .filter_map(|v| resource_set.system_pool.find(*v))
+        .map(|v| system_damage(v, attack));
+    if action.random {
+        mean(damages)
+    } else {
+        damages.sum()
+    }
+}
+
+/// Rates an enemy from its static data only, no battle is simulated:
+///
+/// - effective hp is `hp + armor + shield`, as if armor and shield were spent once
+/// - a system aimed at the opponent deals its first argument plus the enemy `attack`,
+///   random arguments count as their mean; the contract does not tag which argument is
+///   damage, so this holds for the damage systems the bundled data uses and every other
+///   system aimed at the opponent is over-rated
+/// - systems aimed at the caster (heals, armor, buffs) add nothing
+/// - `defense`, `attack_weak`, `defense_weak`, durations and target positions are ignored
+///
+/// Rank mismatches built on this are hints to look at, `DifficultyReport::violations`
+/// filters them before they fail a check
+pub fn rate_enemy(enemy: &Enemy, resource_set: &ResourceSet) -> EnemyRating {
+    let effective_hp = enemy.hp as f64 + enemy.armor as f64 + enemy.shield as f64;
+    // a strategy either picks one action per turn or cycles through all of them, both
+    // average out to the mean over the pool
+    let damage_per_turn = mean(
+        enemy
+            .action_strategy
+            .action_pool
+            .iter()
+            .filter_map(|v| resource_set.action_pool.find(*v))
+            .map(|v| action_damage(v, resource_set, enemy.attack)),
+    );
+    EnemyRating {
+        id: enemy.id,
+        rank: enemy.rank,
+        effective_hp,
+        damage_per_turn,
+        difficulty: (effective_hp * damage_per_turn).sqrt(),
+    }
+}
+
+/// Expected difficulty of an enemy node generated `weight` times, as `ramp` counts it
+fn enemy_node(
+    node: &LevelNode,
+    weight: f64,
+    ratings: &BTreeMap<u16, &EnemyRating>,
+) -> Option<(Vec<u16>, f64)> {
+    let NodeInstance::Enemy(instance) = &node.instance else {
+        return None;
+    };
+    let difficulties = instance
+        .enemy_pool
+        .iter()
+        .filter_map(|v| ratings.get(v))
+        .map(|v| v.difficulty);
+    let difficulty = mean(difficulties) * instance.count as f64 * weight;
+    Some((instance.enemy_pool.clone(), difficulty))
+}
+
+impl DifficultyReport {
+    /// `rank_order` lists ranks from weakest to strongest, the contract does not say what
+    /// each rank means so enemies of ranks missing from it are never compared
+    pub fn new(resource_set: &ResourceSet, rank_order: &[EnemyRank]) -> Self {
+        let enemies = resource_set
+            .enemy_pool
+            .enemy_pool
+            .iter()
+            .map(|v| rate_enemy(v, resource_set))
+            .collect::<Vec<_>>();
+
+        let strength = |rank| rank_order.iter().position(|v| *v == rank);
+        let mut mismatches = vec![];
+        for lower in &enemies {
+            for higher in &enemies {
+                let ranked = match (strength(lower.rank), strength(higher.rank)) {
+                    (Some(lower), Some(higher)) => lower < higher,
+                    _ => false,
+                };
+                if ranked && lower.difficulty > higher.difficulty {
+                    mismatches.push(RankMismatch {
+                        lower: lower.clone(),
+                        higher: higher.clone(),
+                    });
+                }
+            }
+        }
+
+        let ratings = enemies
+            .iter()
+            .map(|v| (v.id, v))
+            .collect::<BTreeMap<_, _>>();
+        let scenes = resource_set
+            .scene_pool
+            .scene_pool
+            .iter()
+            .map(|scene| {
+                let fixed = scene.fixed_nodes.iter().map(|v| (v.point.y, &v.node, 1.0));
+                let partitioned = scene.partition_list.iter().flat_map(|v| {
+                    let weight = node_pool_weight(v);
+                    v.node_pool
+                        .iter()
+                        .map(move |node| (v.start_point.y, node, weight))
+                });
+                let mut points = fixed
+                    .chain(partitioned)
+                    .filter_map(|(y, node, weight)| {
+                        let (enemies, difficulty) = enemy_node(node, weight, &ratings)?;
+                        Some(CurvePoint {
+                            y,
+                            enemies,
+                            difficulty,
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                points.sort_by_key(|v| v.y);
+                SceneCurve {
+                    scene: scene.id,
+                    points,
+                }
+            })
+            .collect();
+
+        Self {
+            enemies,
+            mismatches,
+            scenes,
+        }
+    }
+
+    /// Mismatches where the lower ranked enemy rates more than `tolerance` (a share of the
+    /// higher rating) above the higher ranked one and neither enemy is in `allowed`
+    pub fn violations(&self, tolerance: f64, allowed: &[u16]) -> Vec<&RankMismatch> {
+        self.mismatches
+            .iter()
+            .filter(|v| v.lower.difficulty > v.higher.difficulty * (1.0 + tolerance))
+            .filter(|v| !allowed.contains(&v.lower.id) && !allowed.contains(&v.higher.id))
+            .collect()
+    }
+}
+
+impl fmt::Display for RankMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} enemy {} rates {:.1}, above {} enemy {} at {:.1}",
+            self.lower.rank,
+            self.lower.id,
+            self.lower.difficulty,
+            self.higher.rank,
+            self.higher.id,
+            self.higher.difficulty
+        )
+    }
+}
+
+impl fmt::Display for DifficultyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for enemy in &self.enemies {
+            writeln!(
+                f,
+                "Enemy {} ({}): effective hp {:.1}, damage/turn {:.2}, difficulty {:.1}",
+                enemy.id, enemy.rank, enemy.effective_hp, enemy.damage_per_turn, enemy.difficulty
+            )?;
+        }
+        for mismatch in &self.mismatches {
+            writeln!(f, "rank mismatch: {mismatch}")?;
+        }
+        for scene in &self.scenes {
+            writeln!(f, "Scene {}:", scene.scene)?;
+            for point in &scene.points {
+                writeln!(
+                    f,
+                    "    y {:>3}: {:.1} {:?}",
+                    point.y, point.difficulty, point.enemies
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_difficulty_report() {
+    use crate::loader::NodeEnemy;
+
+    let mut resource_set = ResourceSet::load("./resources".as_ref()).unwrap();
+    let rank_order = [EnemyRank::Rank0, EnemyRank::Rank1, EnemyRank::Rank2];
+    let report = DifficultyReport::new(&resource_set, &rank_order);
+    let ratings = report
+        .enemies
+        .iter()
+        .map(|v| (v.id, v.effective_hp, v.damage_per_turn))
+        .collect::<Vec<_>>();
+    assert_eq!(ratings, vec![(3000, 300.0, 6.25), (3001, 420.0, 8.125)]);
+    assert!(report.mismatches.is_empty());
+
+    let partition = &mut resource_set.scene_pool.scene_pool[0].partition_list[1];
+    partition.node_pool[0].instance = NodeInstance::Enemy(NodeEnemy {
+        count: 2,
+        enemy_pool: vec![3000],
+    });
+    resource_set.enemy_pool.enemy_pool[0].rank = EnemyRank::Rank2;
+    assert!(DifficultyReport::new(&resource_set, &[])
+        .mismatches
+        .is_empty());
+    let report = DifficultyReport::new(&resource_set, &rank_order);
+    assert_eq!(report.mismatches.len(), 1);
+    assert_eq!(report.mismatches[0].lower.id, 3001);
+    // 3001 rates about 1.35 times 3000
+    assert_eq!(report.violations(DEFAULT_TOLERANCE, &[]).len(), 1);
+    assert!(report.violations(1.0, &[]).is_empty());
+    assert!(report.violations(0.0, &[3001]).is_empty());
+    let points = &report.scenes[0].points;
+    assert_eq!(points.len(), 1);
+    // 1 to 2 nodes drawn from a pool of 2 generate each node 0.75 times
+    assert_eq!(
+        (points[0].y, points[0].difficulty),
+        (20, 0.75 * 2.0 * 1875f64.sqrt())
+    );
+}
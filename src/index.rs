@@ -0,0 +1,204 @@
+use eyre::{eyre, Result};
+use molecule::prelude::Reader;
+use spore_warriors_generated as generated;
+use std::collections::HashMap;
+
+use crate::loader::types::{read_u16, read_u16_vec, read_u8};
+use crate::loader::{
+    ActionContext, Card, CardClass, CardPool, Enemy, EnemyPool, EnemyRank, FixedLevelNode, Random,
+    ResourcePoolKind, Scene, ScenePartition, ScenePool,
+};
+
+/// Decoded accessors over a `CardReader`, the borrowed bytes stay in the binary
+#[derive(Debug, Clone, Copy)]
+pub struct CardView<'r>(generated::CardReader<'r>);
+
+impl<'r> CardView<'r> {
+    pub fn reader(&self) -> generated::CardReader<'r> {
+        self.0
+    }
+
+    pub fn id(&self) -> u16 {
+        read_u16(self.0.id())
+    }
+
+    pub fn class(&self) -> CardClass {
+        CardClass::decode(read_u8(self.0.class()))
+    }
+
+    pub fn power_cost(&self) -> u8 {
+        read_u8(self.0.cost())
+    }
+
+    pub fn price(&self) -> Random<u16> {
+        self.0.price().into()
+    }
+
+    pub fn system_pool(&self) -> Vec<u16> {
+        read_u16_vec(self.0.system_pool())
+    }
+
+    pub fn decode(&self) -> Card {
+        self.0.into()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct EnemyView<'r>(generated::EnemyReader<'r>);
+
+impl<'r> EnemyView<'r> {
+    pub fn reader(&self) -> generated::EnemyReader<'r> {
+        self.0
+    }
+
+    pub fn id(&self) -> u16 {
+        read_u16(self.0.id())
+    }
+
+    pub fn rank(&self) -> EnemyRank {
+        EnemyRank::decode(read_u8(self.0.rank()))
+    }
+
+    pub fn hp(&self) -> u16 {
+        read_u16(self.0.hp())
+    }
+
+    pub fn armor(&self) -> u8 {
+        read_u8(self.0.armor())
+    }
+
+    pub fn shield(&self) -> u8 {
+        read_u8(self.0.shield())
+    }
+
+    pub fn attack(&self) -> u8 {
+        read_u8(self.0.attack())
+    }
+
+    pub fn attack_weak(&self) -> u8 {
+        read_u8(self.0.attack_weak())
+    }
+
+    pub fn defense(&self) -> u8 {
+        read_u8(self.0.defense())
+    }
+
+    pub fn defense_weak(&self) -> u8 {
+        read_u8(self.0.defense_weak())
+    }
+
+    pub fn loot_pool(&self) -> Vec<u16> {
+        read_u16_vec(self.0.loot_pool())
+    }
+
+    pub fn action_strategy(&self) -> ActionContext {
+        self.0.action_strategy().into()
+    }
+
+    pub fn decode(&self) -> Enemy {
+        self.0.into()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SceneView<'r>(generated::MapSceneReader<'r>);
+
+impl<'r> SceneView<'r> {
+    pub fn reader(&self) -> generated::MapSceneReader<'r> {
+        self.0
+    }
+
+    pub fn id(&self) -> u16 {
+        read_u16(self.0.id())
+    }
+
+    pub fn width(&self) -> u8 {
+        read_u8(self.0.width())
+    }
+
+    pub fn height(&self) -> u8 {
+        read_u8(self.0.height())
+    }
+
+    pub fn fixed_nodes(&self) -> Vec<FixedLevelNode> {
+        self.0.fixed_nodes().iter().map(Into::into).collect()
+    }
+
+    pub fn partition_list(&self) -> Vec<ScenePartition> {
+        self.0.partition_list().iter().map(Into::into).collect()
+    }
+
+    pub fn decode(&self) -> Scene {
+        self.0.into()
+    }
+}
+
+/// Id lookups into a verified `resources.bin` without decoding the whole pool
+pub struct ResourceIndex<'r> {
+    reader: generated::ResourcePoolReader<'r>,
+    cards: HashMap<u16, usize>,
+    enemies: HashMap<u16, usize>,
+    scenes: HashMap<u16, usize>,
+}
+
+fn index_ids<P: ResourcePoolKind>(reader: generated::ResourcePoolReader) -> HashMap<u16, usize> {
+    P::entry_slices(reader)
+        .into_iter()
+        .enumerate()
+        .map(|(i, (id, _))| (id, i))
+        .collect()
+}
+
+impl<'r> ResourceIndex<'r> {
+    pub fn new(binary: &'r [u8]) -> Result<Self> {
+        let reader = generated::ResourcePoolReader::from_slice(binary)
+            .map_err(|err| eyre!("invalid resource binary: {err}"))?;
+        Ok(Self::from_reader(reader))
+    }
+
+    pub fn from_reader(reader: generated::ResourcePoolReader<'r>) -> Self {
+        Self {
+            cards: index_ids::<CardPool>(reader),
+            enemies: index_ids::<EnemyPool>(reader),
+            scenes: index_ids::<ScenePool>(reader),
+            reader,
+        }
+    }
+
+    pub fn reader(&self) -> generated::ResourcePoolReader<'r> {
+        self.reader
+    }
+
+    pub fn card(&self, id: u16) -> Option<CardView<'r>> {
+        let index = *self.cards.get(&id)?;
+        Some(CardView(self.reader.card_pool().get_unchecked(index)))
+    }
+
+    pub fn enemy(&self, id: u16) -> Option<EnemyView<'r>> {
+        let index = *self.enemies.get(&id)?;
+        Some(EnemyView(self.reader.enemy_pool().get_unchecked(index)))
+    }
+
+    pub fn scene(&self, id: u16) -> Option<SceneView<'r>> {
+        let index = *self.scenes.get(&id)?;
+        Some(SceneView(self.reader.scene_pool().get_unchecked(index)))
+    }
+}
+
+#[test]
+fn test_resource_index_lookups() {
+    let resource_set = crate::loader::ResourceSet::load("./resources".as_ref()).unwrap();
+    let binary = resource_set.clone().into_binary();
+    let index = ResourceIndex::new(&binary).unwrap();
+
+    let card = index.card(101).unwrap();
+    assert_eq!(card.decode(), resource_set.card_pool.card_pool[1]);
+    assert_eq!(card.system_pool(), [2, 3, 4]);
+    let enemy = index.enemy(3001).unwrap();
+    assert_eq!((enemy.hp(), enemy.shield()), (370, 50));
+    assert_eq!(enemy.loot_pool(), [2002]);
+    let scene = index.scene(7001).unwrap();
+    assert_eq!((scene.width(), scene.height()), (35, 50));
+    assert_eq!(scene.decode(), resource_set.scene_pool.scene_pool[0]);
+    assert!(index.card(9999).is_none());
+}
@@ -4,8 +4,25 @@ use loader::{
 use molecule::prelude::{Builder, Entity};
 use spore_warriors_generated as generated;
 
+pub mod canonical;
+pub mod diff;
+pub mod difficulty;
+pub mod index;
+pub mod limits;
 pub mod loader;
+pub mod locale;
+pub mod manifest;
+pub mod patch;
+pub mod ramp;
+pub mod schema;
+pub mod size;
+pub mod split;
+pub mod spreadsheet;
+pub mod text;
+pub mod validate;
+pub mod watch;
 
+#[allow(clippy::too_many_arguments)]
 pub fn generate_resource_binary(
     action_pool: ActionPool,
     card_pool: CardPool,
@@ -0,0 +1,158 @@
+use eyre::{eyre, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+use crate::loader::{
+    entry_schema, find_resource, integer_field, parse_file, visit_pools, PoolVisitor,
+    ResourcePoolKind, ResourceSet, RESOURCE_FILES,
+};
+use crate::validate::ValidationReport;
+
+/// Stem of the optional file holding gameplay limits, checked on top of the type limits
+pub const LIMITS_FILE: &str = "limits";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct Limit {
+    #[serde(default)]
+    pub min: Option<i64>,
+    #[serde(default)]
+    pub max: Option<i64>,
+}
+
+impl Limit {
+    pub fn contains(&self, value: i64) -> bool {
+        self.min.is_none_or(|min| value >= min) && self.max.is_none_or(|max| value <= max)
+    }
+}
+
+/// Soft limits per pool and per field, nested fields are addressed as `a.b`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(transparent)]
+pub struct SoftLimits {
+    pub pools: BTreeMap<String, BTreeMap<String, Limit>>,
+}
+
+struct LimitVisitor<'a> {
+    limits: &'a SoftLimits,
+    resource_set: &'a ResourceSet,
+    report: &'a mut ValidationReport,
+    checked: usize,
+}
+
+impl PoolVisitor for LimitVisitor<'_> {
+    type Error = eyre::Report;
+
+    fn visit<P: ResourcePoolKind>(&mut self) -> Result<()> {
+        let Some(fields) = self.limits.pools.get(P::FILE_NAME) else {
+            return Ok(());
+        };
+        self.checked += 1;
+        let schema = entry_schema::<P::Entry>();
+        if let Some(field) = fields.keys().find(|v| integer_field(&schema, v).is_none()) {
+            return Err(eyre!(
+                "`{field}` in {LIMITS_FILE} is not an integer field of {} entries",
+                P::FILE_NAME
+            ));
+        }
+        for entry in P::of(self.resource_set).entries() {
+            let value = serde_json::to_value(entry)?;
+            for (field, limit) in fields {
+                let pointer = format!("/{}", field.replace('.', "/"));
+                // optional fields left unset have nothing to check
+                let Some(number) = value.pointer(&pointer).and_then(|v| v.as_i64()) else {
+                    continue;
+                };
+                if !limit.contains(number) {
+                    let message = format!("{number} is outside the soft limit {limit}");
+                    self.report
+                        .error(P::FILE_NAME, P::id(entry), field, message);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl SoftLimits {
+    /// Reads `<dir>/limits.json` (or YAML/TOML), `None` when the file does not exist
+    pub fn load(dir: &Path) -> Result<Option<Self>> {
+        let path = find_resource(dir, LIMITS_FILE)?;
+        if !path.exists() {
+            return Ok(None);
+        }
+        parse_file(&path).map(Some)
+    }
+
+    pub fn check(&self, resource_set: &ResourceSet, report: &mut ValidationReport) -> Result<()> {
+        let mut visitor = LimitVisitor {
+            limits: self,
+            resource_set,
+            report,
+            checked: 0,
+        };
+        visit_pools(&mut visitor)?;
+        if visitor.checked < self.pools.len() {
+            let unknown = self
+                .pools
+                .keys()
+                .filter(|v| !RESOURCE_FILES.contains(&v.as_str()))
+                .collect::<Vec<_>>();
+            return Err(eyre!("unknown pools in {LIMITS_FILE}: {unknown:?}"));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(min) = self.min {
+            write!(f, "{min}")?;
+        }
+        write!(f, "..")?;
+        if let Some(max) = self.max {
+            write!(f, "={max}")?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_soft_limits() {
+    let mut resource_set = ResourceSet::load("./resources".as_ref()).unwrap();
+    let limits: SoftLimits = serde_json::from_value(serde_json::json!({
+        "enemies": { "hp": { "min": 1, "max": 9999 } },
+        "warriors": { "gold": { "max": 9999 } }
+    }))
+    .unwrap();
+    let mut report = ValidationReport::default();
+    limits.check(&resource_set, &mut report).unwrap();
+    assert!(report.is_empty());
+
+    let enemy = &mut resource_set.enemy_pool.enemy_pool[0];
+    enemy.hp = 12000;
+    let id = enemy.id;
+    limits.check(&resource_set, &mut report).unwrap();
+    assert_eq!(
+        report.to_string(),
+        format!("enemies {id}: hp 12000 is outside the soft limit 1..=9999\n")
+    );
+
+    let unknown: SoftLimits = serde_json::from_str(r#"{ "monsters": {} }"#).unwrap();
+    assert!(unknown.check(&resource_set, &mut report).is_err());
+    for field in [
+        "health",
+        "action_strategy.random",
+        "action_strategy.action_pool",
+    ] {
+        let limits: SoftLimits =
+            serde_json::from_value(serde_json::json!({ "enemies": { field: { "max": 1 } } }))
+                .unwrap();
+        let err = limits.check(&resource_set, &mut report).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("`{field}` in limits is not an integer field of enemies entries")
+        );
+    }
+}
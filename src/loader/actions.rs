@@ -1,13 +1,12 @@
-use eyre::Result;
 use molecule::prelude::{Builder, Entity};
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use spore_warriors_generated as generated;
-use std::fs;
-use std::path::PathBuf;
 
+use super::types::{read_bool, read_u16, read_u16_vec};
 use crate::{convert_u16, convert_vec};
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 pub struct Action {
     pub id: u16,
     pub random: bool,
@@ -29,26 +28,28 @@ impl From<Action> for generated::Action {
     }
 }
 
-#[derive(Deserialize, Debug)]
-pub struct ActionPool {
-    #[serde(alias = "actions")]
-    pub action_pool: Vec<Action>,
-}
-
-impl ActionPool {
-    pub fn parse_from(path: PathBuf) -> Result<Self> {
-        let raw_action_pool = fs::read_to_string(path)?;
-        Ok(serde_json::from_str(&raw_action_pool)?)
+impl From<generated::ActionReader<'_>> for Action {
+    fn from(value: generated::ActionReader<'_>) -> Self {
+        Self {
+            id: read_u16(value.id()),
+            random: read_bool(value.random()),
+            system_pool: read_u16_vec(value.system_pool()),
+        }
     }
 }
 
-impl From<ActionPool> for generated::ActionVec {
-    fn from(value: ActionPool) -> Self {
-        let pool = value.action_pool;
-        convert_vec!(pool, ActionVec)
-    }
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Default)]
+pub struct ActionPool {
+    #[serde(rename = "actions", alias = "action_pool")]
+    pub action_pool: Vec<Action>,
 }
 
+crate::pool_molecule!(ActionPool {
+    action_pool: Action,
+    file: "actions",
+    molecule: ActionVec, ActionVecReader of Action,
+});
+
 #[test]
 fn test_parse_action_pool() {
     let action_pool = ActionPool::parse_from("./resources/actions.json".into()).unwrap();
@@ -1,17 +1,16 @@
-use eyre::Result;
 use molecule::prelude::{Builder, Entity};
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use spore_warriors_generated as generated;
-use std::fs;
-use std::path::PathBuf;
 
-use super::types::Random;
+use super::types::{read_u16, read_u16_vec, read_u8, Random};
+use super::CardClass;
 use crate::{convert_u16, convert_vec};
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 pub struct Card {
     pub id: u16,
-    pub class: u8,
+    pub class: CardClass,
     pub power_cost: u8,
     pub price: Random<u16>,
     pub system_pool: Vec<u16>,
@@ -28,7 +27,7 @@ impl From<Card> for generated::Card {
         } = value;
         Self::new_builder()
             .id(convert_u16!(id, ResourceId))
-            .class(class.into())
+            .class(u8::from(class).into())
             .cost(power_cost.into())
             .price(price.into())
             .system_pool(convert_vec!(system_pool, ResourceId, ResourceIdVec))
@@ -36,26 +35,30 @@ impl From<Card> for generated::Card {
     }
 }
 
-#[derive(Deserialize, Debug)]
-pub struct CardPool {
-    #[serde(alias = "cards")]
-    pub card_pool: Vec<Card>,
-}
-
-impl CardPool {
-    pub fn parse_from(path: PathBuf) -> Result<Self> {
-        let raw_card_pool = fs::read_to_string(path)?;
-        Ok(serde_json::from_str(&raw_card_pool)?)
+impl From<generated::CardReader<'_>> for Card {
+    fn from(value: generated::CardReader<'_>) -> Self {
+        Self {
+            id: read_u16(value.id()),
+            class: CardClass::decode(read_u8(value.class())),
+            power_cost: read_u8(value.cost()),
+            price: value.price().into(),
+            system_pool: read_u16_vec(value.system_pool()),
+        }
     }
 }
 
-impl From<CardPool> for generated::CardVec {
-    fn from(value: CardPool) -> Self {
-        let pool = value.card_pool;
-        convert_vec!(pool, CardVec)
-    }
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Default)]
+pub struct CardPool {
+    #[serde(rename = "cards", alias = "card_pool")]
+    pub card_pool: Vec<Card>,
 }
 
+crate::pool_molecule!(CardPool {
+    card_pool: Card,
+    file: "cards",
+    molecule: CardVec, CardVecReader of Card,
+});
+
 #[test]
 fn test_parse_card_pool() {
     let card_pool = CardPool::parse_from("./resources/cards.json".into()).unwrap();
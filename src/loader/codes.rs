@@ -0,0 +1,246 @@
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use schemars::JsonSchema;
+use serde::de::{self, Deserializer, Visitor};
+use serde::{Deserialize, Serialize, Serializer};
+use std::fmt;
+
+/// Declares a one-byte code enum that reads and writes either its name or its numeric code,
+/// serializes as the name and encodes to the same byte as the raw code.
+///
+/// Bytes outside the declared codes decode to `Unknown` so a binary written by a newer
+/// contract still loads. Source files may only use declared codes, unless the enum is
+/// marked `open`, whose code list is not fixed by the contract.
+macro_rules! code_enum {
+    ($(#[$meta:meta])* $name:ident, $label:literal open { $($variants:tt)+ }) => {
+        code_enum!(@enum $(#[$meta])* $name, $label, true { $($variants)+ });
+    };
+    ($(#[$meta:meta])* $name:ident, $label:literal { $($variants:tt)+ }) => {
+        code_enum!(@enum $(#[$meta])* $name, $label, false { $($variants)+ });
+    };
+    (@enum $(#[$meta:meta])* $name:ident, $label:literal, $open:literal {
+        $($(#[$variant_meta:meta])* $variant:ident = $code:literal => $text:literal),+ $(,)?
+    }) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant,)+
+            /// A code this crate does not declare
+            Unknown(u8),
+        }
+
+        impl $name {
+            pub const ALL: &'static [Self] = &[$(Self::$variant),+];
+
+            /// Whether codes missing from `ALL` are accepted in source files
+            pub const OPEN: bool = $open;
+
+            pub fn code(self) -> u8 {
+                match self {
+                    $(Self::$variant => $code,)+
+                    Self::Unknown(code) => code,
+                }
+            }
+
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(Self::$variant => $text,)+
+                    Self::Unknown(_) => "unknown",
+                }
+            }
+
+            pub fn is_known(self) -> bool {
+                !matches!(self, Self::Unknown(_))
+            }
+
+            pub fn from_code(code: u8) -> Option<Self> {
+                Self::ALL.iter().copied().find(|v| v.code() == code)
+            }
+
+            pub fn from_name(name: &str) -> Option<Self> {
+                Self::ALL.iter().copied().find(|v| v.name() == name)
+            }
+
+            /// Decodes a byte read from a binary, undeclared codes are kept as `Unknown`
+            pub fn decode(code: u8) -> Self {
+                Self::from_code(code).unwrap_or(Self::Unknown(code))
+            }
+
+            fn unknown<E: de::Error>(value: impl fmt::Display) -> E {
+                E::custom(format!("unknown {} {value}, expected {}", $label, Self::expected()))
+            }
+
+            fn expected() -> String {
+                let known = Self::ALL
+                    .iter()
+                    .map(|v| format!("{} ({})", v.name(), v.code()))
+                    .collect::<Vec<_>>();
+                format!("{} {}", $label, known.join(", "))
+            }
+        }
+
+        impl From<$name> for u8 {
+            fn from(value: $name) -> Self {
+                value.code()
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self {
+                    Self::Unknown(code) => write!(f, "unknown {} {code}", $label),
+                    _ => f.write_str(self.name()),
+                }
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                match self {
+                    Self::Unknown(code) => serializer.serialize_u8(*code),
+                    _ => serializer.serialize_str(self.name()),
+                }
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                struct CodeVisitor;
+
+                impl Visitor<'_> for CodeVisitor {
+                    type Value = $name;
+
+                    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                        write!(f, "one of {}", $name::expected())
+                    }
+
+                    fn visit_u64<E: de::Error>(self, v: u64) -> Result<$name, E> {
+                        let code = u8::try_from(v).map_err(|_| $name::unknown(v))?;
+                        match $name::from_code(code) {
+                            Some(value) => Ok(value),
+                            None if $name::OPEN => Ok($name::Unknown(code)),
+                            None => Err($name::unknown(v)),
+                        }
+                    }
+
+                    fn visit_i64<E: de::Error>(self, v: i64) -> Result<$name, E> {
+                        match u64::try_from(v) {
+                            Ok(v) => self.visit_u64(v),
+                            Err(_) => Err($name::unknown(v)),
+                        }
+                    }
+
+                    /// Map keys always arrive as strings, so a numeric string is read as a code
+                    fn visit_str<E: de::Error>(self, v: &str) -> Result<$name, E> {
+                        if let Ok(code) = v.parse::<u64>() {
+                            return self.visit_u64(code);
+                        }
+                        $name::from_name(v).ok_or_else(|| $name::unknown(format!("`{v}`")))
+                    }
+                }
+
+                deserializer.deserialize_any(CodeVisitor)
+            }
+        }
+
+        impl JsonSchema for $name {
+            fn schema_name() -> String {
+                stringify!($name).to_owned()
+            }
+
+            fn json_schema(_: &mut SchemaGenerator) -> Schema {
+                let names = Self::ALL.iter().map(|v| v.name()).collect::<Vec<_>>();
+                let codes = if Self::OPEN {
+                    serde_json::json!({ "type": "integer", "minimum": 0, "maximum": u8::MAX })
+                } else {
+                    let codes = Self::ALL.iter().map(|v| v.code()).collect::<Vec<_>>();
+                    serde_json::json!({ "type": "integer", "enum": codes })
+                };
+                serde_json::from_value(serde_json::json!({
+                    "anyOf": [{ "type": "string", "enum": names }, codes]
+                }))
+                .expect("valid schema")
+            }
+        }
+    };
+}
+
+code_enum!(CardClass, "card class" {
+    Normal = 0 => "normal",
+    Special = 1 => "special",
+});
+
+code_enum!(ItemClass, "item class" {
+    /// Consumables dropped through `props_pool`
+    Props = 0 => "props",
+    /// Gear dropped through `equipment_pool`
+    Equipment = 1 => "equipment",
+});
+
+code_enum!(
+    /// Only the qualities used by the bundled items are declared, any other byte is accepted.
+    /// Named after their codes until the contract documents what each quality means
+    ItemQuality, "item quality" open {
+    Quality15 = 15 => "quality_15",
+    Quality16 = 16 => "quality_16",
+    Quality21 = 21 => "quality_21",
+});
+
+code_enum!(
+    /// Named after their codes until the contract documents what each rank means, so the
+    /// code order says nothing about strength
+    EnemyRank, "enemy rank" {
+    Rank0 = 0 => "rank_0",
+    Rank1 = 1 => "rank_1",
+    Rank2 = 2 => "rank_2",
+});
+
+code_enum!(
+    #[derive(Default)]
+    TargetType, "target type" {
+    #[default]
+    Caster = 0 => "caster",
+    Opponent = 1 => "opponent",
+});
+
+code_enum!(
+    /// Named after their codes until the contract documents when each trigger fires
+    DurationTrigger, "duration trigger" {
+    Trigger0 = 0 => "trigger_0",
+    Trigger1 = 1 => "trigger_1",
+    Trigger2 = 2 => "trigger_2",
+});
+
+#[test]
+fn test_code_enum_forms() {
+    assert_eq!(
+        serde_json::from_str::<ItemClass>("1").unwrap(),
+        ItemClass::Equipment
+    );
+    assert_eq!(
+        serde_json::from_str::<ItemClass>("\"props\"").unwrap(),
+        ItemClass::Props
+    );
+    assert_eq!(
+        serde_json::to_string(&ItemQuality::Quality21).unwrap(),
+        "\"quality_21\""
+    );
+    assert_eq!(u8::from(ItemQuality::Quality21), 21);
+    let err = serde_json::from_str::<EnemyRank>("7")
+        .unwrap_err()
+        .to_string();
+    assert!(err
+        .contains("unknown enemy rank 7, expected enemy rank rank_0 (0), rank_1 (1), rank_2 (2)"));
+    assert!(serde_json::from_str::<CardClass>("\"legendary\"").is_err());
+    assert_eq!(
+        serde_json::from_str::<EnemyRank>("\"rank_2\"").unwrap(),
+        EnemyRank::Rank2
+    );
+
+    assert_eq!(EnemyRank::decode(7), EnemyRank::Unknown(7));
+    assert_eq!(EnemyRank::decode(7).to_string(), "unknown enemy rank 7");
+    let quality = serde_json::from_str::<ItemQuality>("30").unwrap();
+    assert_eq!(quality, ItemQuality::Unknown(30));
+    assert_eq!(serde_json::to_string(&quality).unwrap(), "30");
+    assert!(serde_json::from_str::<ItemQuality>("\"epic\"").is_err());
+}
@@ -1,13 +1,13 @@
-use eyre::Result;
 use molecule::prelude::{Builder, Entity};
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use spore_warriors_generated as generated;
-use std::fs;
-use std::path::PathBuf;
 
+use super::types::{read_bool, read_u16, read_u16_vec, read_u8};
+use super::EnemyRank;
 use crate::{convert_u16, convert_vec};
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 pub struct ActionContext {
     pub random: bool,
     pub action_pool: Vec<u16>,
@@ -26,10 +26,19 @@ impl From<ActionContext> for generated::ActionContext {
     }
 }
 
-#[derive(Deserialize, Debug)]
+impl From<generated::ActionContextReader<'_>> for ActionContext {
+    fn from(value: generated::ActionContextReader<'_>) -> Self {
+        Self {
+            random: read_bool(value.random()),
+            action_pool: read_u16_vec(value.action_pool()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 pub struct Enemy {
     pub id: u16,
-    pub rank: u8,
+    pub rank: EnemyRank,
     pub hp: u16,
     #[serde(default)]
     pub armor: u8,
@@ -54,7 +63,7 @@ impl From<Enemy> for generated::Enemy {
         let loot_pool = value.loot_pool;
         Self::new_builder()
             .id(convert_u16!(id, ResourceId))
-            .rank(value.rank.into())
+            .rank(u8::from(value.rank).into())
             .hp(convert_u16!(hp, Number))
             .armor(value.armor.into())
             .shield(value.shield.into())
@@ -68,26 +77,36 @@ impl From<Enemy> for generated::Enemy {
     }
 }
 
-#[derive(Deserialize, Debug)]
-pub struct EnemyPool {
-    #[serde(alias = "enemies")]
-    pub enemy_pool: Vec<Enemy>,
-}
-
-impl From<EnemyPool> for generated::EnemyVec {
-    fn from(value: EnemyPool) -> Self {
-        let pool = value.enemy_pool;
-        convert_vec!(pool, EnemyVec)
+impl From<generated::EnemyReader<'_>> for Enemy {
+    fn from(value: generated::EnemyReader<'_>) -> Self {
+        Self {
+            id: read_u16(value.id()),
+            rank: EnemyRank::decode(read_u8(value.rank())),
+            hp: read_u16(value.hp()),
+            armor: read_u8(value.armor()),
+            shield: read_u8(value.shield()),
+            attack: read_u8(value.attack()),
+            attack_weak: read_u8(value.attack_weak()),
+            defense: read_u8(value.defense()),
+            defense_weak: read_u8(value.defense_weak()),
+            loot_pool: read_u16_vec(value.loot_pool()),
+            action_strategy: value.action_strategy().into(),
+        }
     }
 }
 
-impl EnemyPool {
-    pub fn parse_from(path: PathBuf) -> Result<Self> {
-        let raw_enemy_pool = fs::read_to_string(path)?;
-        Ok(serde_json::from_str(&raw_enemy_pool)?)
-    }
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Default)]
+pub struct EnemyPool {
+    #[serde(rename = "enemies", alias = "enemy_pool")]
+    pub enemy_pool: Vec<Enemy>,
 }
 
+crate::pool_molecule!(EnemyPool {
+    enemy_pool: Enemy,
+    file: "enemies",
+    molecule: EnemyVec, EnemyVecReader of Enemy,
+});
+
 #[test]
 fn test_parse_enemy_pool() {
     let enemy_pool = EnemyPool::parse_from("./resources/enemies.json".into()).unwrap();
@@ -0,0 +1,298 @@
+use eyre::{eyre, Result};
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use super::types::{Random, Value};
+
+/// Caster attributes only known when a card is played, never representable in the contract
+#[derive(Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CasterStat {
+    Attack,
+    Physique,
+}
+
+/// Every way a system argument can be written, lowered into `Value` while loading
+#[derive(Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub enum ValueRepr {
+    #[serde(rename = "number", alias = "Number")]
+    Number(u16),
+    #[serde(rename = "random", alias = "Random")]
+    Random(Random<u16>),
+    /// Percentage points, `{ "percent": 50 }` is stored as 50
+    #[serde(rename = "percent")]
+    Percent(u16),
+    #[serde(rename = "stat")]
+    Stat(CasterStat),
+    /// Arithmetic over numbers, `random(min, max)` and caster stats, e.g.
+    /// `"10 - random(2, 4)"`, percentages go through `percent` instead
+    #[serde(rename = "expr")]
+    Expr(String),
+}
+
+impl TryFrom<ValueRepr> for Value {
+    type Error = String;
+
+    fn try_from(value: ValueRepr) -> Result<Self, Self::Error> {
+        match value {
+            ValueRepr::Number(v) | ValueRepr::Percent(v) => Ok(Value::Number(v)),
+            ValueRepr::Random(v) => Ok(Value::Random(v)),
+            ValueRepr::Stat(stat) => Err(stat_error(stat)),
+            ValueRepr::Expr(expr) => lower_expr(&expr).map_err(|err| format!("`{expr}`: {err}")),
+        }
+    }
+}
+
+fn stat_error(stat: CasterStat) -> String {
+    format!(
+        "caster stat `{}` is only known in game, the contract stores constant numbers \
+         or random ranges",
+        format!("{stat:?}").to_lowercase()
+    )
+}
+
+/// Constant fraction or uniform integer range, the two shapes an argument can lower to
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Term {
+    Const(i64, i64),
+    Range(i64, i64),
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 {
+        a.abs().max(1)
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+fn fraction(num: i64, den: i64) -> Term {
+    let sign = if den < 0 { -1 } else { 1 };
+    let divisor = gcd(num, den);
+    Term::Const(sign * num / divisor, sign * den / divisor)
+}
+
+fn integer(term: Term, what: &str) -> Result<i64> {
+    match term {
+        Term::Const(num, 1) => Ok(num),
+        Term::Const(num, den) => Err(eyre!("{what} {num}/{den} is not a whole number")),
+        Term::Range(..) => Err(eyre!("{what} must be constant")),
+    }
+}
+
+fn checked(value: Option<i64>) -> Result<i64> {
+    value.ok_or_else(|| eyre!("arithmetic overflow"))
+}
+
+fn apply(op: char, lhs: Term, rhs: Term) -> Result<Term> {
+    use Term::{Const, Range};
+    // only a sign flip keeps every value of a range equally likely and nothing in between,
+    // `random(2, 4) * 3` draws from {6, 9, 12}, not from 6..=12
+    let scale = |min: i64, max: i64, num: i64, den: i64| match (num, den) {
+        (1, 1) => Ok(Range(min, max)),
+        (-1, 1) => Ok(Range(-max, -min)),
+        _ => Err(eyre!(
+            "scaling random({min}, {max}) by {} is not a uniform range",
+            if den == 1 {
+                num.to_string()
+            } else {
+                format!("{num}/{den}")
+            }
+        )),
+    };
+    match (op, lhs, rhs) {
+        ('+', Const(a, b), Const(c, d)) => {
+            let (ad, cb) = (checked(a.checked_mul(d))?, checked(c.checked_mul(b))?);
+            Ok(fraction(
+                checked(ad.checked_add(cb))?,
+                checked(b.checked_mul(d))?,
+            ))
+        }
+        ('-', lhs, Const(c, d)) => apply('+', lhs, Const(-c, d)),
+        ('-', Const(..), Range(min, max)) => apply('+', lhs, Range(-max, -min)),
+        ('+', Range(min, max), Const(..)) | ('+', Const(..), Range(min, max)) => {
+            let shift = integer(if let Const(..) = rhs { rhs } else { lhs }, "offset")?;
+            Ok(Range(
+                checked(min.checked_add(shift))?,
+                checked(max.checked_add(shift))?,
+            ))
+        }
+        ('*', Const(a, b), Const(c, d)) => Ok(fraction(
+            checked(a.checked_mul(c))?,
+            checked(b.checked_mul(d))?,
+        )),
+        ('*', Range(min, max), Const(num, den)) | ('*', Const(num, den), Range(min, max)) => {
+            scale(min, max, num, den)
+        }
+        ('/', _, Const(0, _)) => Err(eyre!("division by zero")),
+        ('/', lhs, Const(c, d)) => apply('*', lhs, fraction(d, c)),
+        (_, Range(..), Range(..)) => Err(eyre!(
+            "combining two random ranges is not uniform and cannot be stored as one range"
+        )),
+        _ => Err(eyre!("cannot divide by a random range")),
+    }
+}
+
+struct Parser<'a> {
+    input: &'a [u8],
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&mut self) -> Option<u8> {
+        while self.input.get(self.position) == Some(&b' ') {
+            self.position += 1;
+        }
+        self.input.get(self.position).copied()
+    }
+
+    fn expect(&mut self, token: u8) -> Result<()> {
+        match self.peek() {
+            Some(v) if v == token => {
+                self.position += 1;
+                Ok(())
+            }
+            _ => Err(eyre!("expected `{}` at {}", token as char, self.position)),
+        }
+    }
+
+    fn expr(&mut self) -> Result<Term> {
+        let mut lhs = self.term()?;
+        while let Some(op @ (b'+' | b'-')) = self.peek() {
+            self.position += 1;
+            lhs = apply(op as char, lhs, self.term()?)?;
+        }
+        Ok(lhs)
+    }
+
+    fn term(&mut self) -> Result<Term> {
+        let mut lhs = self.factor()?;
+        while let Some(op @ (b'*' | b'/')) = self.peek() {
+            self.position += 1;
+            lhs = apply(op as char, lhs, self.factor()?)?;
+        }
+        Ok(lhs)
+    }
+
+    fn factor(&mut self) -> Result<Term> {
+        match self.peek() {
+            Some(b'(') => {
+                self.position += 1;
+                let term = self.expr()?;
+                self.expect(b')')?;
+                Ok(term)
+            }
+            Some(b'0'..=b'9') => {
+                let number = self.take_while(|v| v.is_ascii_digit());
+                let number = number
+                    .parse::<i64>()
+                    .map_err(|_| eyre!("`{number}` is too large"))?;
+                if self.peek() == Some(b'%') {
+                    return Err(eyre!(
+                        "`%` is not supported in expressions, use `percent` for percentage points"
+                    ));
+                }
+                Ok(Term::Const(number, 1))
+            }
+            Some(v) if v.is_ascii_alphabetic() => {
+                let name = self.take_while(|v| v.is_ascii_alphanumeric() || v == b'_');
+                match name.as_str() {
+                    "random" => {
+                        self.expect(b'(')?;
+                        let min = integer(self.expr()?, "random min")?;
+                        self.expect(b',')?;
+                        let max = integer(self.expr()?, "random max")?;
+                        self.expect(b')')?;
+                        if min > max {
+                            return Err(eyre!("random({min}, {max}) has min above max"));
+                        }
+                        Ok(Term::Range(min, max))
+                    }
+                    "attack" => Err(eyre!(stat_error(CasterStat::Attack))),
+                    "physique" => Err(eyre!(stat_error(CasterStat::Physique))),
+                    _ => Err(eyre!("unknown name `{name}`")),
+                }
+            }
+            Some(v) => Err(eyre!("unexpected `{}` at {}", v as char, self.position)),
+            None => Err(eyre!("unexpected end of expression")),
+        }
+    }
+
+    fn take_while(&mut self, accept: fn(u8) -> bool) -> String {
+        let start = self.position;
+        while self.input.get(self.position).copied().is_some_and(accept) {
+            self.position += 1;
+        }
+        String::from_utf8_lossy(&self.input[start..self.position]).into_owned()
+    }
+}
+
+/// Evaluates an argument expression down to a `Value`, failing when the result is not a
+/// constant or uniform range of whole numbers within `u16`
+pub fn lower_expr(expr: &str) -> Result<Value> {
+    let mut parser = Parser {
+        input: expr.as_bytes(),
+        position: 0,
+    };
+    let term = parser.expr()?;
+    if let Some(v) = parser.peek() {
+        return Err(eyre!("unexpected `{}` at {}", v as char, parser.position));
+    }
+    let bounded = |v: i64| {
+        u16::try_from(v).map_err(|_| eyre!("{v} is outside the allowed range 0..={}", u16::MAX))
+    };
+    match term {
+        Term::Const(..) => Ok(Value::Number(bounded(integer(term, "result")?)?)),
+        Term::Range(min, max) if min == max => Ok(Value::Number(bounded(min)?)),
+        Term::Range(min, max) => Ok(Value::Random(Random {
+            min: bounded(min)?,
+            max: bounded(max)?,
+        })),
+    }
+}
+
+#[test]
+fn test_lower_value_expressions() {
+    let random = |min, max| Value::Random(Random { min, max });
+    for (expr, expected) in [
+        ("5 * 2 + 1", Value::Number(11)),
+        ("10 + random(1, 3) / (0 - 1)", random(7, 9)),
+        ("(10 - 4) / 3", Value::Number(2)),
+        ("random(2, 4) * 1 + 1", random(3, 5)),
+        ("10 - random(1, 3) / 1", random(7, 9)),
+        ("10 - random(1, 3)", random(7, 9)),
+        ("random(5, 5)", Value::Number(5)),
+    ] {
+        assert_eq!(lower_expr(expr).unwrap(), expected, "{expr}");
+    }
+    for (expr, error) in [
+        ("attack * 2", "caster stat `attack`"),
+        ("random(2, 4) * 3 + 1", "by 3 is not a uniform range"),
+        ("random(2, 4) / 2", "by 1/2 is not a uniform range"),
+        ("7 / 2", "7/2 is not a whole number"),
+        ("random(1, 2) + random(1, 2)", "not uniform"),
+        ("1 - 2", "outside the allowed range"),
+        ("7 / 0", "division by zero"),
+        ("random(2, 4) / (0 - 2)", "by -1/2 is not a uniform range"),
+        ("4 / random(1, 2)", "cannot divide by a random range"),
+        ("40 * 50%", "`%` is not supported"),
+        ("2 +", "unexpected end"),
+    ] {
+        let err = lower_expr(expr).unwrap_err().to_string();
+        assert!(err.contains(error), "{expr}: {err}");
+    }
+
+    let system: super::System = serde_json::from_str(
+        r#"{ "id": 1, "system": 0, "args": [{ "percent": 30 }, { "expr": "60 / 2" }, { "expr": "random(1, 2) + 5" }] }"#,
+    )
+    .unwrap();
+    assert_eq!(
+        system.args,
+        [Value::Number(30), Value::Number(30), random(6, 7)]
+    );
+    let err = serde_json::from_str::<super::System>(
+        r#"{ "id": 1, "system": 0, "args": [{ "stat": "physique" }] }"#,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("caster stat `physique`"));
+}
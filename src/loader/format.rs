@@ -0,0 +1,575 @@
+use eyre::{eyre, Result, WrapErr};
+use schemars::gen::SchemaGenerator;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::overlay::REMOVE_KEY;
+use super::template::{TemplateResolver, COMMENT_KEY, TEMPLATES_KEY};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceFormat {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl ResourceFormat {
+    /// Extensions probed for a resource file, in lookup order
+    pub const EXTENSIONS: [&'static str; 4] = ["json", "yaml", "yml", "toml"];
+
+    pub fn from_path(path: &Path) -> Result<Self> {
+        match path.extension().and_then(|v| v.to_str()) {
+            Some("json") => Ok(Self::Json),
+            Some("yaml" | "yml") => Ok(Self::Yaml),
+            Some("toml") => Ok(Self::Toml),
+            _ => Err(eyre!("unsupported resource format: {}", path.display())),
+        }
+    }
+
+    pub fn parse<T: DeserializeOwned>(self, raw: &str) -> Result<T> {
+        Ok(match self {
+            Self::Json => serde_json::from_str(raw)?,
+            // go through a JSON value so enums keep the `{ variant: ... }` map form
+            // instead of the YAML `!tag` representation serde_yaml expects
+            Self::Yaml => serde_json::from_value(serde_yaml::from_str(raw)?)?,
+            Self::Toml => toml::from_str(raw)?,
+        })
+    }
+}
+
+pub fn parse_file<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    let format = ResourceFormat::from_path(path)?;
+    let raw = fs::read_to_string(path).wrap_err_with(|| format!("read {}", path.display()))?;
+    format
+        .parse(&raw)
+        .wrap_err_with(|| format!("parse {}", path.display()))
+}
+
+/// Locates `<stem>.json`, `<stem>.yaml`, `<stem>.yml`, `<stem>.toml` or a `<stem>/` directory
+/// under `dir`, falling back to the JSON path when none exists. More than one of them is an
+/// error, a pool converted to another format would otherwise keep loading the stale file
+pub fn find_resource(dir: &Path, stem: &str) -> Result<PathBuf> {
+    let found = ResourceFormat::EXTENSIONS
+        .iter()
+        .map(|ext| dir.join(format!("{stem}.{ext}")))
+        .chain([dir.join(stem)])
+        .filter(|path| path.exists())
+        .collect::<Vec<_>>();
+    match found.as_slice() {
+        [] => Ok(dir.join(format!("{stem}.json"))),
+        [path] => Ok(path.clone()),
+        paths => Err(eyre!(
+            "{stem} has more than one source, keep only one of {}",
+            paths
+                .iter()
+                .map(|v| v.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+/// Every resource file under `path` in sorted order, or `path` itself if it is a file
+pub fn resource_files(path: &Path) -> Result<Vec<PathBuf>> {
+    if !path.is_dir() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    let mut files = vec![];
+    for entry in fs::read_dir(path)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(resource_files(&path)?);
+        } else if ResourceFormat::from_path(&path).is_ok() {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Untyped content of every file of a pool, before templates are resolved
+#[derive(Debug, Default)]
+pub struct RawPool {
+    pub templates: Map<String, Value>,
+    pub entries: Vec<(PathBuf, Value)>,
+    pub removed: Vec<u16>,
+}
+
+impl RawPool {
+    /// Reads every file of the pool stored under `keys` in pool documents, the first key is
+    /// the current name and the others are older names still accepted
+    pub fn parse(path: &Path, keys: &[&str]) -> Result<Self> {
+        let key = keys[0];
+        let mut raw_pool = Self::default();
+        for file in resource_files(path)? {
+            match parse_file(&file)? {
+                Value::Object(mut document) if !document.contains_key("id") => {
+                    document.remove(COMMENT_KEY);
+                    if let Some(templates) = document.remove(TEMPLATES_KEY) {
+                        let Value::Object(templates) = templates else {
+                            return Err(eyre!(
+                                "`{TEMPLATES_KEY}` in {} must be an object",
+                                file.display()
+                            ));
+                        };
+                        for (name, template) in templates {
+                            if raw_pool.templates.insert(name.clone(), template).is_some() {
+                                return Err(eyre!(
+                                    "duplicate template `{name}` in {}",
+                                    file.display()
+                                ));
+                            }
+                        }
+                    }
+                    if let Some(ids) = document.remove(REMOVE_KEY) {
+                        let ids: Vec<u16> = serde_json::from_value(ids).wrap_err_with(|| {
+                            format!("`{REMOVE_KEY}` in {} must be a list of ids", file.display())
+                        })?;
+                        raw_pool.removed.extend(ids);
+                    }
+                    let mut present = keys.iter().filter(|v| document.contains_key(**v));
+                    if let (Some(first), Some(second)) = (present.next(), present.next()) {
+                        return Err(eyre!(
+                            "both `{first}` and `{second}` in {}, keep only `{key}`",
+                            file.display()
+                        ));
+                    }
+                    if let Some(unknown) = document.keys().find(|v| !keys.contains(&v.as_str())) {
+                        return Err(eyre!(
+                            "unknown key `{unknown}` in {}, expected `{key}`, `{TEMPLATES_KEY}`, \
+                             `{REMOVE_KEY}` or `{COMMENT_KEY}`",
+                            file.display()
+                        ));
+                    }
+                    match keys.iter().find_map(|v| document.remove(*v)) {
+                        Some(Value::Array(list)) => {
+                            let entries = list.into_iter().map(|v| (file.clone(), v));
+                            raw_pool.entries.extend(entries);
+                        }
+                        Some(_) => {
+                            return Err(eyre!("`{key}` in {} must be a list", file.display()));
+                        }
+                        None => {}
+                    }
+                }
+                entry => raw_pool.entries.push((file.clone(), entry)),
+            }
+        }
+        Ok(raw_pool)
+    }
+
+    pub fn resolver(&self) -> TemplateResolver<'_> {
+        TemplateResolver::new(&self.templates, self.entries.iter().map(|(_, v)| v))
+    }
+}
+
+/// Range of a schema integer `format`, e.g. `uint8`, with the matching Rust type name
+fn integer_range(format: &str) -> Option<(String, i128, i128)> {
+    let (signed, bits) = match format.strip_prefix("uint") {
+        Some(bits) => (false, bits),
+        None => (true, format.strip_prefix("int")?),
+    };
+    let bits = bits.parse::<u32>().ok().filter(|v| *v <= 64)?;
+    Some(if signed {
+        (
+            format!("i{bits}"),
+            -(1 << (bits - 1)),
+            (1 << (bits - 1)) - 1,
+        )
+    } else {
+        (format!("u{bits}"), 0, (1 << bits) - 1)
+    })
+}
+
+/// Serde aliases accepted in entries as `(alias, name)`, schemars leaves them out of the
+/// schemas so the range check resolves them here
+const KEY_ALIASES: [(&str, &str); 13] = [
+    ("system_id", "system"),
+    ("Number", "number"),
+    ("Random", "random"),
+    ("Enemy", "enemy"),
+    ("TreasureChest", "treasure_chest"),
+    ("RecoverPoint", "recover_point"),
+    ("ItemMerchant", "item_merchant"),
+    ("CardMerchant", "card_merchant"),
+    ("Unknown", "unknown"),
+    ("Campsite", "campsite"),
+    ("Barrier", "barrier"),
+    ("StartingPoint", "starting_point"),
+    ("TargetingPoint", "targeting_point"),
+];
+
+/// The schema property `key` stands for, under its own name or as an alias
+fn property<'a>(properties: &'a Map<String, Value>, key: &str) -> Option<&'a Value> {
+    properties.get(key).or_else(|| {
+        let (_, name) = KEY_ALIASES.iter().find(|(alias, _)| *alias == key)?;
+        properties.get(*name)
+    })
+}
+
+/// Whether `value` holds `key` under its own name or one of its aliases
+fn has_key(value: &Value, key: &str) -> bool {
+    value.get(key).is_some()
+        || KEY_ALIASES
+            .iter()
+            .any(|(alias, name)| *name == key && value.get(*alias).is_some())
+}
+
+/// Follows a `$ref` into the root schema `definitions`
+fn resolve_ref<'a>(root: &'a Value, schema: &'a Value) -> &'a Value {
+    let name = schema
+        .get("$ref")
+        .and_then(|v| v.as_str())
+        .and_then(|v| v.strip_prefix("#/definitions/"));
+    match name.and_then(|v| root.get("definitions")?.get(v)) {
+        Some(definition) => resolve_ref(root, definition),
+        None => schema,
+    }
+}
+
+/// Schema of the integer field at the `.` separated `path` of an entry, `None` when the path
+/// names no field or the field is not an integer
+pub fn integer_field<'a>(root: &'a Value, path: &str) -> Option<&'a Value> {
+    let mut schema = resolve_ref(root, root);
+    for key in path.split('.') {
+        let branches = ["anyOf", "oneOf", "allOf"]
+            .iter()
+            .filter_map(|v| schema.get(*v)?.as_array())
+            .flatten()
+            .map(|v| resolve_ref(root, v));
+        schema = std::iter::once(schema)
+            .chain(branches)
+            .find_map(|v| property(v.get("properties")?.as_object()?, key))
+            .map(|v| resolve_ref(root, v))?;
+    }
+    let integer = |v: &Value| v.as_str() == Some("integer");
+    match schema.get("type")? {
+        Value::Array(types) => types.iter().any(integer).then_some(schema),
+        name => integer(name).then_some(schema),
+    }
+}
+
+/// Whether `value` has the shape of `schema`, used to pick the `anyOf` branches to descend
+fn shape_matches(root: &Value, schema: &Value, value: &Value) -> bool {
+    let schema = resolve_ref(root, schema);
+    let type_matches = |name: &str| match name {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "string" => value.is_string(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        _ => true,
+    };
+    let types_match = match schema.get("type") {
+        Some(Value::String(name)) => type_matches(name),
+        Some(Value::Array(names)) => names.iter().filter_map(|v| v.as_str()).any(type_matches),
+        _ => true,
+    };
+    let enum_matches = schema
+        .get("enum")
+        .and_then(|v| v.as_array())
+        .is_none_or(|v| v.contains(value));
+    let required_present = schema
+        .get("required")
+        .and_then(|v| v.as_array())
+        .is_none_or(|keys| {
+            keys.iter()
+                .filter_map(|v| v.as_str())
+                .all(|key| has_key(value, key))
+        });
+    let branches_match = ["anyOf", "oneOf"].iter().all(|key| {
+        schema
+            .get(key)
+            .and_then(|v| v.as_array())
+            .is_none_or(|v| v.iter().any(|v| shape_matches(root, v, value)))
+    });
+    types_match && enum_matches && required_present && branches_match
+}
+
+/// Checks every integer of `value` against the type range its schema `format` declares,
+/// so the error can name the field and the range instead of a bare serde message
+fn check_integer_ranges(root: &Value, schema: &Value, value: &Value, field: &str) -> Result<()> {
+    let schema = resolve_ref(root, schema);
+    if let Some(all) = schema.get("allOf").and_then(|v| v.as_array()) {
+        for schema in all {
+            check_integer_ranges(root, schema, value, field)?;
+        }
+    }
+    for key in ["anyOf", "oneOf"] {
+        let Some(branches) = schema.get(key).and_then(|v| v.as_array()) else {
+            continue;
+        };
+        let mut matching = branches
+            .iter()
+            .filter(|v| shape_matches(root, v, value))
+            .map(|v| check_integer_ranges(root, v, value, field));
+        if let Some(first) = matching.next() {
+            if first.is_err() && matching.all(|v| v.is_err()) {
+                return first;
+            }
+        }
+    }
+    let number = value
+        .as_i64()
+        .map(i128::from)
+        .or_else(|| value.as_u64().map(i128::from));
+    let range = schema
+        .get("format")
+        .and_then(|v| v.as_str())
+        .and_then(integer_range);
+    if let (Some(number), Some((integer, min, max))) = (number, range) {
+        if !(min..=max).contains(&number) {
+            return Err(eyre!(
+                "`{field}` {number} is out of the {integer} range {min}..={max}"
+            ));
+        }
+    }
+    let join = |key: &str| {
+        if field.is_empty() {
+            key.to_owned()
+        } else {
+            format!("{field}.{key}")
+        }
+    };
+    match value {
+        Value::Object(object) => {
+            let properties = schema.get("properties").and_then(|v| v.as_object());
+            let additional = schema.get("additionalProperties").filter(|v| v.is_object());
+            for (key, value) in object {
+                let property = properties.and_then(|v| property(v, key)).or(additional);
+                if let Some(property) = property {
+                    check_integer_ranges(root, property, value, &join(key))?;
+                }
+            }
+        }
+        Value::Array(list) => {
+            for (i, value) in list.iter().enumerate() {
+                let item = match schema.get("items") {
+                    Some(Value::Array(items)) => items.get(i),
+                    items => items,
+                };
+                if let Some(item) = item {
+                    check_integer_ranges(root, item, value, &format!("{field}[{i}]"))?;
+                }
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Deserializes a resolved entry, naming the offending field. Integers are first checked
+/// against the entry `schema` so an overflow reports the allowed range of its type
+pub fn deserialize_entry<E: DeserializeOwned>(schema: &Value, value: Value) -> Result<E> {
+    check_integer_ranges(schema, schema, &value, "")?;
+    serde_path_to_error::deserialize(value)
+        .map_err(|err| eyre!("`{}`: {}", err.path(), err.inner()))
+}
+
+/// Root JSON schema of one pool entry, as passed to `deserialize_entry`
+pub fn entry_schema<E: JsonSchema>() -> Value {
+    let schema = SchemaGenerator::default().into_root_schema_for::<E>();
+    serde_json::to_value(schema).expect("serialize schema")
+}
+
+/// Loads a pool from a single file or a directory of files. Each file holds either a whole
+/// pool document or one entry (recognized by its top-level `id`), entries are merged in path
+/// order and ids must be unique across all files. Pool documents may declare `templates`
+/// that entries, like entries of other entries, inherit from through `extends`
+pub fn parse_pool<P, E>(
+    path: &Path,
+    keys: &[&str],
+    entries: fn(&mut P) -> &mut Vec<E>,
+    id: fn(&E) -> u16,
+) -> Result<P>
+where
+    P: Default,
+    E: DeserializeOwned + JsonSchema,
+{
+    let (pool, removed) = parse_overlay_pool(path, keys, entries, id)?;
+    if !removed.is_empty() {
+        return Err(eyre!(
+            "`{REMOVE_KEY}` is only allowed in overlays, found in {}",
+            path.display()
+        ));
+    }
+    Ok(pool)
+}
+
+/// Same as `parse_pool`, but also collects the ids listed under `remove`
+pub fn parse_overlay_pool<P, E>(
+    path: &Path,
+    keys: &[&str],
+    entries: fn(&mut P) -> &mut Vec<E>,
+    id: fn(&E) -> u16,
+) -> Result<(P, Vec<u16>)>
+where
+    P: Default,
+    E: DeserializeOwned + JsonSchema,
+{
+    let raw_pool = RawPool::parse(path, keys)?;
+    let resolver = raw_pool.resolver();
+    let schema = entry_schema::<E>();
+    let mut pool = P::default();
+    let mut origins = BTreeMap::<u16, &PathBuf>::new();
+    for (file, raw_entry) in &raw_pool.entries {
+        let context = || match raw_entry.get("id") {
+            Some(id) => format!("entry {id} in {}", file.display()),
+            None => format!("entry in {}", file.display()),
+        };
+        let entry: E = resolver
+            .resolve(raw_entry)
+            .and_then(|v| deserialize_entry(&schema, v))
+            .wrap_err_with(context)?;
+        if let Some(origin) = origins.insert(id(&entry), file) {
+            return Err(eyre!(
+                "duplicate id {} in {}, already defined in {}",
+                id(&entry),
+                file.display(),
+                origin.display()
+            ));
+        }
+        entries(&mut pool).push(entry);
+    }
+    Ok((pool, raw_pool.removed))
+}
+
+#[test]
+fn test_parse_yaml_and_toml_pools() {
+    use super::{CardPool, ScenePool};
+    use molecule::prelude::Entity;
+    use spore_warriors_generated as generated;
+
+    let dir = std::env::temp_dir().join("spore-warriors-formats");
+    fs::create_dir_all(&dir).unwrap();
+
+    let raw_scenes: serde_json::Value = parse_file("./resources/scenes.json".as_ref()).unwrap();
+    fs::write(
+        dir.join("scenes.yaml"),
+        serde_yaml::to_string(&raw_scenes).unwrap(),
+    )
+    .unwrap();
+    let json: generated::MapSceneVec = ScenePool::parse_from("./resources/scenes.json".into())
+        .unwrap()
+        .into();
+    let yaml: generated::MapSceneVec =
+        ScenePool::parse_from(find_resource(&dir, "scenes").unwrap())
+            .unwrap()
+            .into();
+    assert_eq!(json.as_slice(), yaml.as_slice());
+
+    let raw_cards: serde_json::Value = parse_file("./resources/cards.json".as_ref()).unwrap();
+    fs::write(dir.join("cards.toml"), toml::to_string(&raw_cards).unwrap()).unwrap();
+    let json: generated::CardVec = CardPool::parse_from("./resources/cards.json".into())
+        .unwrap()
+        .into();
+    let toml: generated::CardVec = CardPool::parse_from(find_resource(&dir, "cards").unwrap())
+        .unwrap()
+        .into();
+    assert_eq!(json.as_slice(), toml.as_slice());
+
+    let dir = std::env::temp_dir().join("spore-warriors-ambiguous");
+    fs::create_dir_all(dir.join("cards")).unwrap();
+    fs::write(dir.join("cards.json"), "{}").unwrap();
+    let err = find_resource(&dir, "cards").unwrap_err().to_string();
+    assert!(err.starts_with("cards has more than one source"), "{err}");
+}
+
+#[test]
+fn test_parse_pool_directory() {
+    use super::CardPool;
+
+    let dir = std::env::temp_dir().join("spore-warriors-card-dir");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("starter")).unwrap();
+    let raw_cards: serde_json::Value = parse_file("./resources/cards.json".as_ref()).unwrap();
+    let cards = raw_cards["cards"].as_array().unwrap();
+    fs::write(
+        dir.join("a.json"),
+        serde_json::json!({ "cards": cards[..2] }).to_string(),
+    )
+    .unwrap();
+    fs::write(
+        dir.join("starter/102.yaml"),
+        serde_yaml::to_string(&cards[2]).unwrap(),
+    )
+    .unwrap();
+    let merged = CardPool::parse_from(dir.clone()).unwrap();
+    let single = CardPool::parse_from("./resources/cards.json".into()).unwrap();
+    assert_eq!(merged, single);
+
+    fs::write(dir.join("b.json"), cards[0].to_string()).unwrap();
+    let err = CardPool::parse_from(dir.clone()).unwrap_err();
+    assert!(err.to_string().starts_with("duplicate id 100"));
+
+    fs::remove_file(dir.join("b.json")).unwrap();
+    // pool documents written before the key rename still load
+    fs::write(dir.join("c.json"), r#"{ "card_pool": [] }"#).unwrap();
+    assert_eq!(CardPool::parse_from(dir.clone()).unwrap(), single);
+    fs::write(dir.join("c.json"), r#"{ "cards": [], "card_pool": [] }"#).unwrap();
+    let err = CardPool::parse_from(dir.clone()).unwrap_err();
+    assert!(err
+        .to_string()
+        .starts_with("both `cards` and `card_pool` in "));
+    fs::write(dir.join("c.json"), r#"{ "card": [] }"#).unwrap();
+    let err = CardPool::parse_from(dir).unwrap_err();
+    assert!(err.to_string().starts_with("unknown key `card` in "));
+}
+
+#[test]
+fn test_integer_out_of_range() {
+    use super::EnemyPool;
+
+    let dir = std::env::temp_dir().join("spore-warriors-ranges");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("enemies.yaml");
+    fs::write(
+        &path,
+        "id: 3001\nrank: 0\nhp: 100\narmor: 300\nloot_pool: []\n\
+         action_strategy: { random: false, action_pool: [10] }\n",
+    )
+    .unwrap();
+    let err = EnemyPool::parse_from(path.clone()).unwrap_err();
+    assert_eq!(
+        format!("{err:#}"),
+        format!(
+            "entry 3001 in {}: `armor` 300 is out of the u8 range 0..=255",
+            path.display()
+        )
+    );
+
+    let system = serde_json::json!({ "id": 1, "system": 0, "args": [{ "number": 70000 }] });
+    let err =
+        deserialize_entry::<super::System>(&entry_schema::<super::System>(), system).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`args[0].number` 70000 is out of the u16 range 0..=65535"
+    );
+    let system = serde_json::json!({ "id": 1, "system_id": 70000, "args": [{ "Number": 1 }] });
+    let err =
+        deserialize_entry::<super::System>(&entry_schema::<super::System>(), system).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`system_id` 70000 is out of the u16 range 0..=65535"
+    );
+    let node = serde_json::json!({ "Enemy": { "count": 300, "enemy_pool": [] } });
+    let schema = entry_schema::<super::NodeInstance>();
+    let err = deserialize_entry::<super::NodeInstance>(&schema, node).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`Enemy.count` 300 is out of the u8 range 0..=255"
+    );
+    let system = serde_json::json!({ "id": 1, "system": "damage", "args": [] });
+    let err =
+        deserialize_entry::<super::System>(&entry_schema::<super::System>(), system).unwrap_err();
+    assert!(
+        err.to_string().starts_with("`system`: invalid type"),
+        "{err}"
+    );
+}
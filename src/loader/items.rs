@@ -1,18 +1,17 @@
-use eyre::Result;
 use molecule::prelude::{Builder, Entity};
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use spore_warriors_generated as generated;
-use std::fs;
-use std::path::PathBuf;
 
-use super::types::Random;
+use super::types::{read_u16, read_u16_vec, read_u8, Random};
+use super::{ItemClass, ItemQuality};
 use crate::{convert_u16, convert_vec};
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 pub struct Item {
     pub id: u16,
-    pub class: u8,
-    pub quality: u8,
+    pub class: ItemClass,
+    pub quality: ItemQuality,
     pub random_weight: Random<u8>,
     pub price: Random<u16>,
     pub system_pool: Vec<u16>,
@@ -30,8 +29,8 @@ impl From<Item> for generated::Item {
         } = value;
         Self::new_builder()
             .id(convert_u16!(id, ResourceId))
-            .class(class.into())
-            .quality(quality.into())
+            .class(u8::from(class).into())
+            .quality(u8::from(quality).into())
             .random_weight(random_weight.into())
             .price(price.into())
             .system_pool(convert_vec!(system_pool, ResourceId, ResourceIdVec))
@@ -39,26 +38,31 @@ impl From<Item> for generated::Item {
     }
 }
 
-#[derive(Deserialize, Debug)]
-pub struct ItemPool {
-    #[serde(alias = "items")]
-    pub item_pool: Vec<Item>,
-}
-
-impl ItemPool {
-    pub fn parse_from(path: PathBuf) -> Result<Self> {
-        let raw_item_pool = fs::read_to_string(path)?;
-        Ok(serde_json::from_str(&raw_item_pool)?)
+impl From<generated::ItemReader<'_>> for Item {
+    fn from(value: generated::ItemReader<'_>) -> Self {
+        Self {
+            id: read_u16(value.id()),
+            class: ItemClass::decode(read_u8(value.class())),
+            quality: ItemQuality::decode(read_u8(value.quality())),
+            random_weight: value.random_weight().into(),
+            price: value.price().into(),
+            system_pool: read_u16_vec(value.system_pool()),
+        }
     }
 }
 
-impl From<ItemPool> for generated::ItemVec {
-    fn from(value: ItemPool) -> Self {
-        let pool = value.item_pool;
-        convert_vec!(pool, ItemVec)
-    }
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Default)]
+pub struct ItemPool {
+    #[serde(rename = "items", alias = "item_pool")]
+    pub item_pool: Vec<Item>,
 }
 
+crate::pool_molecule!(ItemPool {
+    item_pool: Item,
+    file: "items",
+    molecule: ItemVec, ItemVecReader of Item,
+});
+
 #[test]
 fn test_parse_item_pool() {
     let item_pool = ItemPool::parse_from("./resources/items.json".into()).unwrap();
@@ -0,0 +1,196 @@
+use eyre::{eyre, Result};
+use molecule::prelude::Entity;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use spore_warriors_generated as generated;
+use std::path::PathBuf;
+
+use super::format::parse_pool;
+use super::{
+    ActionPool, CardPool, EnemyPool, ItemPool, LootPool, ResourceSet, ScenePool, SystemPool,
+    WarriorPool,
+};
+
+/// One pool of the `ResourcePool`, implemented by each loader pool type so that tooling
+/// can be written once for all of them
+pub trait ResourcePoolKind:
+    Serialize + DeserializeOwned + Default + Clone + PartialEq + Into<Self::Molecule>
+{
+    type Entry: Serialize + DeserializeOwned + JsonSchema + Clone + PartialEq;
+    type Molecule: Entity;
+
+    /// Source file stem under a resource directory, also the pool name in reports
+    const FILE_NAME: &'static str;
+    /// Singular entry name for human readable reports
+    const ENTRY_NAME: &'static str;
+    /// `ResourcePool` field holding this pool, the pool name in binary tooling
+    const FIELD_NAME: &'static str;
+
+    fn entries(&self) -> &Vec<Self::Entry>;
+    fn entries_mut(&mut self) -> &mut Vec<Self::Entry>;
+    fn id(entry: &Self::Entry) -> u16;
+    fn of(resource_set: &ResourceSet) -> &Self;
+    fn of_mut(resource_set: &mut ResourceSet) -> &mut Self;
+
+    /// This pool's vector inside a `ResourcePool` binary
+    fn pool_slice<'r>(pool: generated::ResourcePoolReader<'r>) -> &'r [u8];
+    /// Id and binary of each entry of this pool inside a `ResourcePool` binary
+    fn entry_slices<'r>(pool: generated::ResourcePoolReader<'r>) -> Vec<(u16, &'r [u8])>;
+    /// Verifies each entry binary and packs them into this pool's vector
+    fn molecule_from_entries(entries: &[Vec<u8>]) -> Result<Self::Molecule>;
+    fn set_molecule(
+        builder: generated::ResourcePoolBuilder,
+        molecule: Self::Molecule,
+    ) -> generated::ResourcePoolBuilder;
+
+    fn molecule_from_slice(slice: &[u8]) -> Result<Self::Molecule> {
+        Self::Molecule::from_slice(slice)
+            .map_err(|err| eyre!("invalid {} vector: {err}", Self::FIELD_NAME))
+    }
+
+    fn parse_from(path: PathBuf) -> Result<Self> {
+        let keys = [Self::FILE_NAME, Self::FIELD_NAME];
+        parse_pool(&path, &keys, Self::entries_mut, Self::id)
+    }
+
+    fn find(&self, id: u16) -> Option<&Self::Entry> {
+        self.entries().iter().find(|v| Self::id(v) == id)
+    }
+}
+
+/// Implements `ResourcePoolKind`, the molecule conversions and the inherent `parse_from` for
+/// a pool struct holding `$entry`s in its `$field` field, which is also the `ResourcePool`
+/// field of its `$vec` vector of `$table` tables
+#[macro_export]
+macro_rules! pool_molecule {
+    ($pool:ident {
+        $field:ident: $entry:ident,
+        file: $file:literal,
+        molecule: $vec:ident, $reader:ident of $table:ident $(,)?
+    }) => {
+        impl $pool {
+            /// Same as `ResourcePoolKind::parse_from`, for callers without the trait in scope
+            pub fn parse_from(path: std::path::PathBuf) -> eyre::Result<Self> {
+                <Self as $crate::loader::ResourcePoolKind>::parse_from(path)
+            }
+        }
+
+        impl $crate::loader::ResourcePoolKind for $pool {
+            type Entry = $entry;
+            type Molecule = generated::$vec;
+
+            const FILE_NAME: &'static str = $file;
+            const ENTRY_NAME: &'static str = stringify!($entry);
+            const FIELD_NAME: &'static str = stringify!($field);
+
+            fn entries(&self) -> &Vec<$entry> {
+                &self.$field
+            }
+
+            fn entries_mut(&mut self) -> &mut Vec<$entry> {
+                &mut self.$field
+            }
+
+            fn id(entry: &$entry) -> u16 {
+                entry.id
+            }
+
+            fn of(resource_set: &$crate::loader::ResourceSet) -> &Self {
+                &resource_set.$field
+            }
+
+            fn of_mut(resource_set: &mut $crate::loader::ResourceSet) -> &mut Self {
+                &mut resource_set.$field
+            }
+
+            fn pool_slice<'r>(pool: generated::ResourcePoolReader<'r>) -> &'r [u8] {
+                molecule::prelude::Reader::as_slice(&pool.$field())
+            }
+
+            fn entry_slices<'r>(pool: generated::ResourcePoolReader<'r>) -> Vec<(u16, &'r [u8])> {
+                let vector = pool.$field();
+                (0..vector.len())
+                    .map(|i| {
+                        let entry = vector.get_unchecked(i);
+                        let id = $crate::loader::types::read_u16(entry.id());
+                        (id, molecule::prelude::Reader::as_slice(&entry))
+                    })
+                    .collect()
+            }
+
+            fn molecule_from_entries(entries: &[Vec<u8>]) -> eyre::Result<Self::Molecule> {
+                let entries = entries
+                    .iter()
+                    .map(|v| {
+                        <generated::$table as molecule::prelude::Entity>::from_slice(v).map_err(
+                            |err| eyre::eyre!("invalid {} entry: {err}", stringify!($field)),
+                        )
+                    })
+                    .collect::<eyre::Result<Vec<_>>>()?;
+                let builder = <Self::Molecule as molecule::prelude::Entity>::new_builder();
+                Ok(molecule::prelude::Builder::build(&builder.set(entries)))
+            }
+
+            fn set_molecule(
+                builder: generated::ResourcePoolBuilder,
+                molecule: Self::Molecule,
+            ) -> generated::ResourcePoolBuilder {
+                builder.$field(molecule)
+            }
+        }
+
+        impl From<$pool> for generated::$vec {
+            fn from(value: $pool) -> Self {
+                let pool = value.$field;
+                $crate::convert_vec!(pool, $vec)
+            }
+        }
+
+        impl From<generated::$reader<'_>> for $pool {
+            fn from(value: generated::$reader<'_>) -> Self {
+                Self {
+                    $field: value.iter().map(Into::into).collect(),
+                }
+            }
+        }
+    };
+}
+
+/// Generic callback over every pool kind, since closures cannot be generic. Visitors that
+/// cannot fail use `Infallible` as their error
+pub trait PoolVisitor {
+    type Error;
+
+    fn visit<P: ResourcePoolKind>(&mut self) -> Result<(), Self::Error>;
+}
+
+/// Visits each pool kind in `ResourcePool` field order
+pub fn visit_pools<V: PoolVisitor>(visitor: &mut V) -> Result<(), V::Error> {
+    visitor.visit::<ActionPool>()?;
+    visitor.visit::<CardPool>()?;
+    visitor.visit::<SystemPool>()?;
+    visitor.visit::<EnemyPool>()?;
+    visitor.visit::<LootPool>()?;
+    visitor.visit::<ScenePool>()?;
+    visitor.visit::<WarriorPool>()?;
+    visitor.visit::<ItemPool>()
+}
+
+#[test]
+fn test_pool_kinds_match_resource_files() {
+    struct Names(Vec<&'static str>);
+
+    impl PoolVisitor for Names {
+        type Error = std::convert::Infallible;
+
+        fn visit<P: ResourcePoolKind>(&mut self) -> Result<(), Self::Error> {
+            self.0.push(P::FILE_NAME);
+            Ok(())
+        }
+    }
+
+    let mut names = Names(vec![]);
+    let Ok(()) = visit_pools(&mut names);
+    assert_eq!(names.0, super::RESOURCE_FILES);
+}
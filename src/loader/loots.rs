@@ -1,14 +1,12 @@
-use eyre::Result;
 use molecule::prelude::{Builder, Entity};
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use spore_warriors_generated as generated;
-use std::fs;
-use std::path::PathBuf;
 
-use crate::loader::types::Random;
+use crate::loader::types::{read_u16, read_u16_vec, read_u8, Random};
 use crate::{convert_opt, convert_u16, convert_vec};
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 pub struct Package {
     pub size: u8,
     pub item_pool: Vec<u16>,
@@ -24,7 +22,16 @@ impl From<Package> for generated::Package {
     }
 }
 
-#[derive(Deserialize, Debug)]
+impl From<generated::PackageReader<'_>> for Package {
+    fn from(value: generated::PackageReader<'_>) -> Self {
+        Self {
+            size: read_u8(value.size()),
+            item_pool: read_u16_vec(value.item_pool()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 pub struct Loot {
     pub id: u16,
     pub gold: Random<u16>,
@@ -55,26 +62,31 @@ impl From<Loot> for generated::Loot {
     }
 }
 
-#[derive(Deserialize, Debug)]
-pub struct LootPool {
-    #[serde(alias = "loots")]
-    pub loot_pool: Vec<Loot>,
-}
-
-impl LootPool {
-    pub fn parse_from(path: PathBuf) -> Result<Self> {
-        let raw_loot_pool = fs::read_to_string(path)?;
-        Ok(serde_json::from_str(&raw_loot_pool)?)
+impl From<generated::LootReader<'_>> for Loot {
+    fn from(value: generated::LootReader<'_>) -> Self {
+        Self {
+            id: read_u16(value.id()),
+            gold: value.gold().into(),
+            score: value.score().into(),
+            card_pool: value.card_pool().into(),
+            props_pool: value.props_pool().to_opt().map(Into::into),
+            equipment_pool: value.equipment_pool().to_opt().map(Into::into),
+        }
     }
 }
 
-impl From<LootPool> for generated::LootVec {
-    fn from(value: LootPool) -> Self {
-        let pool = value.loot_pool;
-        convert_vec!(pool, LootVec)
-    }
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Default)]
+pub struct LootPool {
+    #[serde(rename = "loots", alias = "loot_pool")]
+    pub loot_pool: Vec<Loot>,
 }
 
+crate::pool_molecule!(LootPool {
+    loot_pool: Loot,
+    file: "loots",
+    molecule: LootVec, LootVecReader of Loot,
+});
+
 #[test]
 fn test_parse_loot_pool() {
     let loot_pool = LootPool::parse_from("./resources/loots.json".into()).unwrap();
@@ -1,18 +1,33 @@
 mod actions;
 mod cards;
+mod codes;
 mod enemies;
+mod expr;
+mod format;
 mod items;
+mod kind;
 mod loots;
+mod overlay;
 mod scenes;
+mod set;
 mod systems;
-mod types;
+mod template;
+pub(crate) mod types;
 mod warriors;
 
 pub use actions::*;
 pub use cards::*;
+pub use codes::*;
 pub use enemies::*;
+pub use expr::*;
+pub use format::*;
 pub use items::*;
+pub use kind::*;
 pub use loots::*;
+pub use overlay::*;
 pub use scenes::*;
+pub use set::*;
 pub use systems::*;
+pub use template::*;
+pub use types::{Coordinate, GridSize, Random, Value};
 pub use warriors::*;
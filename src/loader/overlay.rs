@@ -0,0 +1,195 @@
+use eyre::{eyre, Result, WrapErr};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use super::format::{find_resource, parse_overlay_pool};
+use super::{visit_pools, PoolVisitor, ResourcePoolKind, ResourceSet};
+
+/// Overlay document field listing the ids to delete from the pool below
+pub const REMOVE_KEY: &str = "remove";
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlayAction {
+    Added,
+    Replaced,
+    Removed,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct OverlayChange {
+    pub overlay: PathBuf,
+    pub pool: String,
+    pub id: u16,
+    pub action: OverlayAction,
+}
+
+/// An entry touched by more than one overlay, the last one in `changes` wins
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct OverlayConflict {
+    pub pool: String,
+    pub id: u16,
+    pub changes: Vec<OverlayChange>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Default)]
+pub struct OverlayReport {
+    pub changes: Vec<OverlayChange>,
+    pub conflicts: Vec<OverlayConflict>,
+}
+
+struct OverlayVisitor<'a> {
+    dir: &'a Path,
+    resource_set: &'a mut ResourceSet,
+    changes: Vec<OverlayChange>,
+}
+
+impl PoolVisitor for OverlayVisitor<'_> {
+    type Error = eyre::Report;
+
+    fn visit<P: ResourcePoolKind>(&mut self) -> Result<()> {
+        let path = find_resource(self.dir, P::FILE_NAME)?;
+        if !path.exists() {
+            return Ok(());
+        }
+        self.overlay::<P>(&path)
+            .wrap_err_with(|| format!("apply {} overlay {}", P::FILE_NAME, self.dir.display()))
+    }
+}
+
+impl OverlayVisitor<'_> {
+    fn overlay<P: ResourcePoolKind>(&mut self, path: &Path) -> Result<()> {
+        let keys = [P::FILE_NAME, P::FIELD_NAME];
+        let (mut pool, removed) = parse_overlay_pool(path, &keys, P::entries_mut, P::id)?;
+        let upserted = pool.entries_mut();
+        let current = P::of_mut(self.resource_set).entries_mut();
+        let mut change = |id, action| {
+            self.changes.push(OverlayChange {
+                overlay: self.dir.to_owned(),
+                pool: P::FILE_NAME.to_owned(),
+                id,
+                action,
+            })
+        };
+        for removed_id in removed {
+            if upserted.iter().any(|v| P::id(v) == removed_id) {
+                return Err(eyre!("id {removed_id} is both removed and redefined"));
+            }
+            let position = current
+                .iter()
+                .position(|v| P::id(v) == removed_id)
+                .ok_or_else(|| eyre!("cannot remove unknown id {removed_id}"))?;
+            current.remove(position);
+            change(removed_id, OverlayAction::Removed);
+        }
+        for entry in upserted.drain(..) {
+            let entry_id = P::id(&entry);
+            match current.iter().position(|v| P::id(v) == entry_id) {
+                Some(position) => {
+                    current[position] = entry;
+                    change(entry_id, OverlayAction::Replaced);
+                }
+                None => {
+                    current.push(entry);
+                    change(entry_id, OverlayAction::Added);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ResourceSet {
+    /// Adds, replaces or removes entries by id with the pools found under an overlay
+    /// directory, pool files missing from the overlay are left untouched
+    pub fn apply_overlay(&mut self, dir: &Path) -> Result<Vec<OverlayChange>> {
+        let mut visitor = OverlayVisitor {
+            dir,
+            resource_set: self,
+            changes: vec![],
+        };
+        visit_pools(&mut visitor)?;
+        Ok(visitor.changes)
+    }
+
+    /// Loads the base directory and applies each overlay on top of it in order
+    pub fn load_with_overlays(base: &Path, overlays: &[PathBuf]) -> Result<(Self, OverlayReport)> {
+        let mut resource_set = Self::load(base)?;
+        let mut report = OverlayReport::default();
+        for overlay in overlays {
+            report.changes.extend(resource_set.apply_overlay(overlay)?);
+        }
+        let mut touched = BTreeMap::<(&str, u16), Vec<&OverlayChange>>::new();
+        for change in &report.changes {
+            touched
+                .entry((&change.pool, change.id))
+                .or_default()
+                .push(change);
+        }
+        report.conflicts = touched
+            .into_iter()
+            .filter(|(_, changes)| changes.len() > 1)
+            .map(|((pool, id), changes)| OverlayConflict {
+                pool: pool.to_owned(),
+                id,
+                changes: changes.into_iter().cloned().collect(),
+            })
+            .collect();
+        Ok((resource_set, report))
+    }
+}
+
+impl fmt::Display for OverlayConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let changes = self
+            .changes
+            .iter()
+            .map(|v| format!("{:?} by {}", v.action, v.overlay.display()).to_lowercase())
+            .collect::<Vec<_>>();
+        write!(f, "{} {}: {}", self.pool, self.id, changes.join(", then "))
+    }
+}
+
+#[test]
+fn test_apply_overlays() {
+    let dir = std::env::temp_dir().join("spore-warriors-overlays");
+    let (first, second) = (dir.join("first"), dir.join("second"));
+    std::fs::create_dir_all(&first).unwrap();
+    std::fs::create_dir_all(&second).unwrap();
+    std::fs::write(
+        first.join("enemies.json"),
+        r#"{
+            "enemies": [
+                { "id": 3001, "extends": 3002, "hp": 400 },
+                {
+                    "id": 3002,
+                    "rank": 2,
+                    "hp": 100,
+                    "loot_pool": [],
+                    "action_strategy": { "random": false, "action_pool": [10] }
+                }
+            ]
+        }"#,
+    )
+    .unwrap();
+    std::fs::write(second.join("enemies.yaml"), "enemies: []\nremove: [3002]\n").unwrap();
+
+    let base = ResourceSet::load("./resources".as_ref()).unwrap();
+    let mut overlaid = base.clone();
+    let changes = overlaid.apply_overlay(&first).unwrap();
+    assert_eq!(changes.len(), 2);
+    assert_eq!(changes[1].action, OverlayAction::Added);
+    assert_eq!(overlaid.enemy_pool.enemy_pool.len(), 3);
+
+    let (overlaid, report) =
+        ResourceSet::load_with_overlays("./resources".as_ref(), &[first, second.clone()]).unwrap();
+    assert_eq!(overlaid.enemy_pool.enemy_pool.len(), 2);
+    assert_eq!(overlaid.enemy_pool.enemy_pool[1].hp, 400);
+    assert_eq!(report.conflicts.len(), 1);
+    assert_eq!(report.conflicts[0].id, 3002);
+
+    let err = base.clone().apply_overlay(&second).unwrap_err();
+    assert!(format!("{err:?}").contains("cannot remove unknown id 3002"));
+}
@@ -1,15 +1,13 @@
-use eyre::Result;
 use molecule::prelude::{Builder, Entity};
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use spore_warriors_generated as generated;
-use std::fs;
-use std::path::PathBuf;
 
 use crate::{convert_u16, convert_vec};
 
-use super::types::{Coordinate, GridSize, Random};
+use super::types::{read_bool, read_u16, read_u16_vec, read_u8, Coordinate, GridSize, Random};
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 pub struct NodeEnemy {
     pub count: u8,
     pub enemy_pool: Vec<u16>,
@@ -26,7 +24,7 @@ impl From<NodeEnemy> for generated::NodeInstanceUnion {
     }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 pub struct NodeTreasureChest {
     pub pick: u8,
     pub count: u8,
@@ -49,7 +47,7 @@ impl From<NodeTreasureChest> for generated::NodeInstanceUnion {
     }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 pub struct NodeItemMerchant {
     pub count: u8,
     pub item_pool: Vec<u16>,
@@ -66,7 +64,7 @@ impl From<NodeItemMerchant> for generated::NodeInstanceUnion {
     }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 pub struct NodeCardMerchant {
     pub count: u8,
     pub card_pool: Vec<u16>,
@@ -83,7 +81,7 @@ impl From<NodeCardMerchant> for generated::NodeInstanceUnion {
     }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 pub struct NodeUnknown {
     pub count: u8,
     pub system_pool: Vec<u16>,
@@ -100,27 +98,27 @@ impl From<NodeUnknown> for generated::NodeInstanceUnion {
     }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 pub enum NodeInstance {
-    #[serde(alias = "enemy")]
+    #[serde(rename = "enemy", alias = "Enemy")]
     Enemy(NodeEnemy),
-    #[serde(alias = "treasure_chest")]
+    #[serde(rename = "treasure_chest", alias = "TreasureChest")]
     TreasureChest(NodeTreasureChest),
-    #[serde(alias = "recover_point")]
+    #[serde(rename = "recover_point", alias = "RecoverPoint")]
     RecoverPoint(u8),
-    #[serde(alias = "item_merchant")]
+    #[serde(rename = "item_merchant", alias = "ItemMerchant")]
     ItemMerchant(NodeItemMerchant),
-    #[serde(alias = "card_merchant")]
+    #[serde(rename = "card_merchant", alias = "CardMerchant")]
     CardMerchant(NodeCardMerchant),
-    #[serde(alias = "unknown")]
+    #[serde(rename = "unknown", alias = "Unknown")]
     Unknown(NodeUnknown),
-    #[serde(alias = "campsite")]
+    #[serde(rename = "campsite", alias = "Campsite")]
     Campsite(u16),
-    #[serde(alias = "barrier")]
+    #[serde(rename = "barrier", alias = "Barrier")]
     Barrier,
-    #[serde(alias = "starting_point")]
+    #[serde(rename = "starting_point", alias = "StartingPoint")]
     StartingPoint,
-    #[serde(alias = "targeting_point")]
+    #[serde(rename = "targeting_point", alias = "TargetingPoint")]
     TargetingPoint,
 }
 
@@ -156,7 +154,54 @@ impl From<NodeInstance> for generated::NodeInstance {
     }
 }
 
-#[derive(Deserialize, Debug)]
+impl From<generated::NodeInstanceReader<'_>> for NodeInstance {
+    fn from(value: generated::NodeInstanceReader<'_>) -> Self {
+        match value.to_enum() {
+            generated::NodeInstanceUnionReader::NodeEnemy(v) => NodeInstance::Enemy(NodeEnemy {
+                count: read_u8(v.count()),
+                enemy_pool: read_u16_vec(v.enemy_pool()),
+            }),
+            generated::NodeInstanceUnionReader::NodeTreasureChest(v) => {
+                NodeInstance::TreasureChest(NodeTreasureChest {
+                    pick: read_u8(v.pick()),
+                    count: read_u8(v.count()),
+                    item_pool: read_u16_vec(v.item_pool()),
+                })
+            }
+            generated::NodeInstanceUnionReader::NodeRecoverPoint(v) => {
+                NodeInstance::RecoverPoint(read_u8(v.hp_percent()))
+            }
+            generated::NodeInstanceUnionReader::NodeItemMerchant(v) => {
+                NodeInstance::ItemMerchant(NodeItemMerchant {
+                    count: read_u8(v.count()),
+                    item_pool: read_u16_vec(v.item_pool()),
+                })
+            }
+            generated::NodeInstanceUnionReader::NodeCardMerchant(v) => {
+                NodeInstance::CardMerchant(NodeCardMerchant {
+                    count: read_u8(v.count()),
+                    card_pool: read_u16_vec(v.card_pool()),
+                })
+            }
+            generated::NodeInstanceUnionReader::NodeUnknown(v) => {
+                NodeInstance::Unknown(NodeUnknown {
+                    count: read_u8(v.count()),
+                    system_pool: read_u16_vec(v.system_pool()),
+                })
+            }
+            generated::NodeInstanceUnionReader::NodeCampsite(v) => {
+                NodeInstance::Campsite(read_u16(v.card_system()))
+            }
+            generated::NodeInstanceUnionReader::NodeBarrier(_) => NodeInstance::Barrier,
+            generated::NodeInstanceUnionReader::NodeStartingPoint(_) => NodeInstance::StartingPoint,
+            generated::NodeInstanceUnionReader::NodeTargetingPoint(_) => {
+                NodeInstance::TargetingPoint
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 pub struct LevelNode {
     pub visible: bool,
     #[serde(default)]
@@ -174,7 +219,17 @@ impl From<LevelNode> for generated::LevelNode {
     }
 }
 
-#[derive(Deserialize, Debug)]
+impl From<generated::LevelNodeReader<'_>> for LevelNode {
+    fn from(value: generated::LevelNodeReader<'_>) -> Self {
+        Self {
+            visible: read_bool(value.visible()),
+            size: value.size().into(),
+            instance: value.node().into(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 pub struct FixedLevelNode {
     pub point: Coordinate,
     pub node: LevelNode,
@@ -189,7 +244,16 @@ impl From<FixedLevelNode> for generated::FixedLevelNode {
     }
 }
 
-#[derive(Deserialize, Debug)]
+impl From<generated::FixedLevelNodeReader<'_>> for FixedLevelNode {
+    fn from(value: generated::FixedLevelNodeReader<'_>) -> Self {
+        Self {
+            point: value.point().into(),
+            node: value.node().into(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 pub struct ScenePartition {
     pub start_point: Coordinate,
     pub end_point: Coordinate,
@@ -209,7 +273,18 @@ impl From<ScenePartition> for generated::ScenePartition {
     }
 }
 
-#[derive(Deserialize, Debug)]
+impl From<generated::ScenePartitionReader<'_>> for ScenePartition {
+    fn from(value: generated::ScenePartitionReader<'_>) -> Self {
+        Self {
+            start_point: value.start_point().into(),
+            end_point: value.end_point().into(),
+            count: value.count().into(),
+            node_pool: value.node_pool().iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 pub struct Scene {
     pub id: u16,
     pub width: u8,
@@ -237,26 +312,30 @@ impl From<Scene> for generated::MapScene {
     }
 }
 
-#[derive(Deserialize, Debug)]
-pub struct ScenePool {
-    #[serde(alias = "scenes")]
-    pub scene_pool: Vec<Scene>,
-}
-
-impl ScenePool {
-    pub fn parse_from(path: PathBuf) -> Result<Self> {
-        let raw_scene_pool = fs::read_to_string(path)?;
-        Ok(serde_json::from_str(&raw_scene_pool)?)
+impl From<generated::MapSceneReader<'_>> for Scene {
+    fn from(value: generated::MapSceneReader<'_>) -> Self {
+        Self {
+            id: read_u16(value.id()),
+            width: read_u8(value.width()),
+            height: read_u8(value.height()),
+            fixed_nodes: value.fixed_nodes().iter().map(Into::into).collect(),
+            partition_list: value.partition_list().iter().map(Into::into).collect(),
+        }
     }
 }
 
-impl From<ScenePool> for generated::MapSceneVec {
-    fn from(value: ScenePool) -> Self {
-        let pool = value.scene_pool;
-        convert_vec!(pool, MapSceneVec)
-    }
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Default)]
+pub struct ScenePool {
+    #[serde(rename = "scenes", alias = "scene_pool")]
+    pub scene_pool: Vec<Scene>,
 }
 
+crate::pool_molecule!(ScenePool {
+    scene_pool: Scene,
+    file: "scenes",
+    molecule: MapSceneVec, MapSceneVecReader of MapScene,
+});
+
 #[test]
 fn test_parse_scene_pool() {
     let scene_pool = ScenePool::parse_from("./resources/scenes.json".into()).unwrap();
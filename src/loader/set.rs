@@ -0,0 +1,136 @@
+use eyre::{eyre, Result, WrapErr};
+use molecule::prelude::Reader;
+use spore_warriors_generated as generated;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::format::{find_resource, resource_files};
+use super::{
+    visit_pools, ActionPool, CardPool, EnemyPool, ItemPool, LootPool, PoolVisitor,
+    ResourcePoolKind, ScenePool, SystemPool, WarriorPool,
+};
+
+/// Source file stems of each pool under a resource directory, in `ResourcePool` field order
+pub const RESOURCE_FILES: [&str; 8] = [
+    "actions", "cards", "systems", "enemies", "loots", "scenes", "warriors", "items",
+];
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ResourceSet {
+    pub action_pool: ActionPool,
+    pub card_pool: CardPool,
+    pub system_pool: SystemPool,
+    pub enemy_pool: EnemyPool,
+    pub loot_pool: LootPool,
+    pub scene_pool: ScenePool,
+    pub warrior_pool: WarriorPool,
+    pub item_pool: ItemPool,
+}
+
+struct PoolLoader<'a> {
+    dir: &'a Path,
+    only: Option<&'a str>,
+    resource_set: &'a mut ResourceSet,
+    loaded: bool,
+}
+
+impl PoolVisitor for PoolLoader<'_> {
+    type Error = eyre::Report;
+
+    fn visit<P: ResourcePoolKind>(&mut self) -> Result<()> {
+        if self.only.is_some_and(|name| name != P::FILE_NAME) {
+            return Ok(());
+        }
+        *P::of_mut(self.resource_set) = find_resource(self.dir, P::FILE_NAME)
+            .and_then(P::parse_from)
+            .wrap_err_with(|| format!("load {} pool", P::FILE_NAME))?;
+        self.loaded = true;
+        Ok(())
+    }
+}
+
+impl ResourceSet {
+    pub fn load(dir: &Path) -> Result<Self> {
+        let mut resource_set = Self::default();
+        visit_pools(&mut PoolLoader {
+            dir,
+            only: None,
+            resource_set: &mut resource_set,
+            loaded: false,
+        })?;
+        Ok(resource_set)
+    }
+
+    /// Reparses a single pool, named by its `RESOURCE_FILES` stem, in place
+    pub fn reload_pool(&mut self, dir: &Path, name: &str) -> Result<()> {
+        let mut loader = PoolLoader {
+            dir,
+            only: Some(name),
+            resource_set: self,
+            loaded: false,
+        };
+        visit_pools(&mut loader)?;
+        if !loader.loaded {
+            return Err(eyre!("unknown pool `{name}`"));
+        }
+        Ok(())
+    }
+
+    pub fn from_binary(binary: &[u8]) -> Result<Self> {
+        let pool = generated::ResourcePoolReader::from_slice(binary)
+            .map_err(|err| eyre!("invalid resource binary: {err}"))?;
+        Ok(Self {
+            action_pool: pool.action_pool().into(),
+            card_pool: pool.card_pool().into(),
+            system_pool: pool.system_pool().into(),
+            enemy_pool: pool.enemy_pool().into(),
+            loot_pool: pool.loot_pool().into(),
+            scene_pool: pool.scene_pool().into(),
+            warrior_pool: pool.warrior_pool().into(),
+            item_pool: pool.item_pool().into(),
+        })
+    }
+
+    /// Every existing pool file under `dir`, overlays may only provide some of them
+    pub fn source_files(dir: &Path) -> Result<Vec<PathBuf>> {
+        let mut files = vec![];
+        for name in RESOURCE_FILES {
+            let path = find_resource(dir, name)?;
+            if path.exists() {
+                files.extend(resource_files(&path)?);
+            }
+        }
+        Ok(files)
+    }
+
+    /// Loads a resource directory, or decodes a `resources.bin` when `path` is a file
+    pub fn open(path: &Path) -> Result<Self> {
+        if path.is_dir() {
+            Self::load(path)
+        } else {
+            let binary = fs::read(path).wrap_err_with(|| format!("read {}", path.display()))?;
+            Self::from_binary(&binary)
+        }
+    }
+
+    pub fn into_binary(self) -> Vec<u8> {
+        crate::generate_resource_binary(
+            self.action_pool,
+            self.card_pool,
+            self.system_pool,
+            self.enemy_pool,
+            self.loot_pool,
+            self.scene_pool,
+            self.warrior_pool,
+            self.item_pool,
+        )
+    }
+}
+
+#[test]
+fn test_resource_set_binary_roundtrip() {
+    let resource_set = ResourceSet::load("./resources".as_ref()).unwrap();
+    let binary = resource_set.clone().into_binary();
+    let decoded = ResourceSet::from_binary(&binary).unwrap();
+    assert_eq!(resource_set, decoded);
+}
@@ -1,35 +1,47 @@
 use eyre::Result;
 use molecule::prelude::{Builder, Entity};
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use spore_warriors_generated as generated;
-use std::path::PathBuf;
-use std::{fmt::Debug, fs};
+use std::fmt::Debug;
+use std::path::Path;
 
-use crate::loader::types::Value;
-use crate::{convert_opt, convert_u16, convert_vec};
+use super::format::{find_resource, parse_file};
+use super::{DurationTrigger, TargetType};
+use crate::loader::types::{read_u16, read_u8, Value};
+use crate::{convert_opt, convert_u16};
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 pub struct Duration {
-    pub trigger: u8,
+    pub trigger: DurationTrigger,
     pub count: u8,
 }
 
 impl From<Duration> for generated::Duration {
     fn from(value: Duration) -> Self {
         generated::Duration::new_builder()
-            .trigger(value.trigger.into())
+            .trigger(u8::from(value.trigger).into())
             .count(value.count.into())
             .build()
     }
 }
 
-#[derive(Deserialize, Debug)]
+impl From<generated::DurationReader<'_>> for Duration {
+    fn from(value: generated::DurationReader<'_>) -> Self {
+        Self {
+            trigger: DurationTrigger::decode(read_u8(value.trigger())),
+            count: read_u8(value.count()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 pub struct System {
     pub id: u16,
-    #[serde(alias = "system")]
+    #[serde(rename = "system", alias = "system_id")]
     pub system_id: u16,
     #[serde(default)]
-    pub target_type: u8,
+    pub target_type: TargetType,
     pub args: Vec<Value>,
     pub duration: Option<Duration>,
 }
@@ -45,30 +57,67 @@ impl From<System> for generated::System {
         generated::System::new_builder()
             .id(convert_u16!(id, ResourceId))
             .system_id(convert_u16!(system_id, SystemId))
-            .target_type(value.target_type.into())
+            .target_type(u8::from(value.target_type).into())
             .args(args)
             .duration(convert_opt!(duration, DurationOpt))
             .build()
     }
 }
 
-#[derive(Deserialize, Debug)]
+impl From<generated::SystemReader<'_>> for System {
+    fn from(value: generated::SystemReader<'_>) -> Self {
+        Self {
+            id: read_u16(value.id()),
+            system_id: read_u16(value.system_id()),
+            target_type: TargetType::decode(read_u8(value.target_type())),
+            args: value.args().iter().map(Into::into).collect(),
+            duration: value.duration().to_opt().map(Into::into),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Default)]
 pub struct SystemPool {
-    #[serde(alias = "systems")]
+    #[serde(rename = "systems", alias = "system_pool")]
     pub system_pool: Vec<System>,
 }
 
-impl SystemPool {
-    pub fn parse_from(path: PathBuf) -> Result<Self> {
-        let raw_effect_pool = fs::read_to_string(path)?;
-        Ok(serde_json::from_str(&raw_effect_pool)?)
-    }
+crate::pool_molecule!(SystemPool {
+    system_pool: System,
+    file: "systems",
+    molecule: SystemVec, SystemVecReader of System,
+});
+
+/// Stem of the optional file declaring the `system_id`s the contract implements
+pub const SYSTEM_IDS_FILE: &str = "system_ids";
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct SystemDeclaration {
+    pub id: u16,
+    #[serde(default)]
+    pub name: String,
+    /// Whether systems of this id may carry a `Duration`
+    #[serde(default)]
+    pub duration: bool,
 }
 
-impl From<SystemPool> for generated::SystemVec {
-    fn from(value: SystemPool) -> Self {
-        let pool = value.system_pool;
-        convert_vec!(pool, SystemVec)
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Default)]
+pub struct SystemDeclarations {
+    pub system_ids: Vec<SystemDeclaration>,
+}
+
+impl SystemDeclarations {
+    /// Reads `<dir>/system_ids.json` (or YAML/TOML), `None` when the file does not exist
+    pub fn load(dir: &Path) -> Result<Option<Self>> {
+        let path = find_resource(dir, SYSTEM_IDS_FILE)?;
+        if !path.exists() {
+            return Ok(None);
+        }
+        parse_file(&path).map(Some)
+    }
+
+    pub fn get(&self, system_id: u16) -> Option<&SystemDeclaration> {
+        self.system_ids.iter().find(|v| v.id == system_id)
     }
 }
 
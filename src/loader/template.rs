@@ -0,0 +1,141 @@
+use eyre::{eyre, Result};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+/// Document-level object of named partial entries that entries can extend
+pub const TEMPLATES_KEY: &str = "templates";
+/// Entry field naming a template, or the id of another entry in the same pool
+pub const EXTENDS_KEY: &str = "extends";
+
+/// Free-form note kept in the source files only, never inherited through `extends`
+pub const COMMENT_KEY: &str = "__comment__";
+
+/// Overrides `base` with `overlay`, merging nested objects and replacing everything else
+pub fn merge_value(base: &mut Value, overlay: &Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(key) {
+                    Some(field) if field.is_object() && value.is_object() => {
+                        merge_value(field, value)
+                    }
+                    _ => {
+                        base.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay.clone(),
+    }
+}
+
+pub struct TemplateResolver<'a> {
+    templates: &'a Map<String, Value>,
+    entries: BTreeMap<u64, &'a Value>,
+}
+
+impl<'a> TemplateResolver<'a> {
+    pub fn new(
+        templates: &'a Map<String, Value>,
+        entries: impl Iterator<Item = &'a Value>,
+    ) -> Self {
+        let entries = entries
+            .filter_map(|entry| Some((entry.get("id")?.as_u64()?, entry)))
+            .collect();
+        Self { templates, entries }
+    }
+
+    /// Returns `entry` with its `extends` chain flattened into it
+    pub fn resolve(&self, entry: &Value) -> Result<Value> {
+        self.resolve_chain(entry, &mut vec![])
+    }
+
+    fn resolve_chain(&self, entry: &Value, chain: &mut Vec<String>) -> Result<Value> {
+        let Some(parent) = entry.get(EXTENDS_KEY) else {
+            return Ok(entry.clone());
+        };
+        let (name, base) = match parent {
+            Value::String(name) => (
+                format!("template `{name}`"),
+                self.templates
+                    .get(name)
+                    .ok_or_else(|| eyre!("unknown template `{name}`"))?,
+            ),
+            Value::Number(id) => (
+                format!("entry {id}"),
+                id.as_u64()
+                    .and_then(|id| self.entries.get(&id).copied())
+                    .ok_or_else(|| eyre!("cannot extend unknown entry {id}"))?,
+            ),
+            _ => {
+                return Err(eyre!(
+                    "`{EXTENDS_KEY}` must be a template name or an entry id"
+                ))
+            }
+        };
+        if chain.contains(&name) {
+            return Err(eyre!(
+                "circular `{EXTENDS_KEY}`: {} -> {name}",
+                chain.join(" -> ")
+            ));
+        }
+        chain.push(name);
+        let mut resolved = self.resolve_chain(base, chain)?;
+        chain.pop();
+        if let Value::Object(fields) = &mut resolved {
+            fields.remove(COMMENT_KEY);
+        }
+        merge_value(&mut resolved, entry);
+        if let Value::Object(fields) = &mut resolved {
+            fields.remove(EXTENDS_KEY);
+        }
+        Ok(resolved)
+    }
+}
+
+#[test]
+fn test_resolve_templates() {
+    use super::{Enemy, EnemyPool};
+
+    let document = serde_json::json!({
+        "templates": {
+            "boss": {
+                "rank": 1,
+                "hp": 300,
+                "armor": 10,
+                "loot_pool": [2001],
+                "action_strategy": { "random": true, "action_pool": [10, 11] }
+            },
+            "tough_boss": { "extends": "boss", "armor": 30 }
+        },
+        "enemies": [
+            { "id": 3000, "extends": "tough_boss", "hp": 270 },
+            { "id": 3001, "extends": 3000, "action_strategy": { "random": false } },
+            { "id": 3002, "extends": 3003 },
+            { "id": 3003, "extends": 3002 }
+        ]
+    });
+    let dir = std::env::temp_dir().join("spore-warriors-templates");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("enemies.json");
+    std::fs::write(&path, document.to_string()).unwrap();
+    let err = EnemyPool::parse_from(path.clone()).unwrap_err();
+    assert!(format!("{err:?}").contains("circular `extends`"));
+
+    let mut document = document;
+    document["enemies"].as_array_mut().unwrap().truncate(2);
+    std::fs::write(&path, document.to_string()).unwrap();
+    let enemies = EnemyPool::parse_from(path).unwrap().enemy_pool;
+    let expected: Vec<Enemy> = serde_json::from_value(serde_json::json!([
+        {
+            "id": 3000, "rank": 1, "hp": 270, "armor": 30, "loot_pool": [2001],
+            "action_strategy": { "random": true, "action_pool": [10, 11] }
+        },
+        {
+            "id": 3001, "rank": 1, "hp": 270, "armor": 30, "loot_pool": [2001],
+            "action_strategy": { "random": false, "action_pool": [10, 11] }
+        }
+    ]))
+    .unwrap();
+    assert_eq!(enemies, expected);
+}
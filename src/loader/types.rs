@@ -1,7 +1,12 @@
-use molecule::prelude::{Builder, Entity};
-use serde::Deserialize;
+use molecule::prelude::{Builder, Entity, Reader};
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use spore_warriors_generated as generated;
 
+use super::expr::ValueRepr;
+
 #[macro_export]
 macro_rules! convert_u16 {
     ($val:ident, $gen:ident) => {
@@ -31,14 +36,31 @@ macro_rules! convert_vec {
         generated::$vgen::new_builder()
             .set(
                 $val.into_iter()
-                    .map(|v| crate::convert_u16!(v, $gen))
+                    .map(|v| $crate::convert_u16!(v, $gen))
                     .collect(),
             )
             .build()
     };
 }
 
-#[derive(Deserialize, Debug)]
+pub fn read_u8<'r, R: Reader<'r>>(reader: R) -> u8 {
+    reader.as_slice()[0]
+}
+
+pub fn read_bool<'r, R: Reader<'r>>(reader: R) -> bool {
+    read_u8(reader) != 0
+}
+
+pub fn read_u16<'r, R: Reader<'r>>(reader: R) -> u16 {
+    let raw = reader.as_slice();
+    u16::from_le_bytes([raw[0], raw[1]])
+}
+
+pub fn read_u16_vec(reader: generated::ResourceIdVecReader<'_>) -> Vec<u16> {
+    reader.iter().map(read_u16).collect()
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 pub struct Random<T: Sized> {
     pub min: T,
     pub max: T,
@@ -55,6 +77,15 @@ impl From<Random<u16>> for generated::RandomNumber {
     }
 }
 
+impl From<generated::RandomNumberReader<'_>> for Random<u16> {
+    fn from(value: generated::RandomNumberReader<'_>) -> Self {
+        Self {
+            min: read_u16(value.lower_bound()),
+            max: read_u16(value.upper_bound()),
+        }
+    }
+}
+
 impl From<Random<u8>> for generated::RandomByte {
     fn from(value: Random<u8>) -> Self {
         Self::new_builder()
@@ -64,7 +95,16 @@ impl From<Random<u8>> for generated::RandomByte {
     }
 }
 
-#[derive(Deserialize, Debug, Default)]
+impl From<generated::RandomByteReader<'_>> for Random<u8> {
+    fn from(value: generated::RandomByteReader<'_>) -> Self {
+        Self {
+            min: read_u8(value.lower_bound()),
+            max: read_u8(value.upper_bound()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Default)]
 pub struct GridSize {
     pub x: u8,
     pub y: u8,
@@ -79,7 +119,16 @@ impl From<GridSize> for generated::Size {
     }
 }
 
-#[derive(Deserialize, Debug)]
+impl From<generated::SizeReader<'_>> for GridSize {
+    fn from(value: generated::SizeReader<'_>) -> Self {
+        Self {
+            x: read_u8(value.x()),
+            y: read_u8(value.y()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 pub struct Coordinate {
     pub x: u8,
     pub y: u8,
@@ -94,14 +143,35 @@ impl From<Coordinate> for generated::Coordinate {
     }
 }
 
-#[derive(Deserialize, Debug)]
+impl From<generated::CoordinateReader<'_>> for Coordinate {
+    fn from(value: generated::CoordinateReader<'_>) -> Self {
+        Self {
+            x: read_u8(value.x()),
+            y: read_u8(value.y()),
+        }
+    }
+}
+
+/// A system argument as the contract stores it, authored in any `ValueRepr` form
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(try_from = "ValueRepr")]
 pub enum Value {
-    #[serde(alias = "number")]
+    #[serde(rename = "number", alias = "Number")]
     Number(u16),
-    #[serde(alias = "random")]
+    #[serde(rename = "random", alias = "Random")]
     Random(Random<u16>),
 }
 
+impl JsonSchema for Value {
+    fn schema_name() -> String {
+        "Value".to_owned()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        ValueRepr::json_schema(gen)
+    }
+}
+
 impl From<Value> for generated::Value {
     fn from(value: Value) -> Self {
         let union = match value {
@@ -112,3 +182,11 @@ impl From<Value> for generated::Value {
     }
 }
 
+impl From<generated::ValueReader<'_>> for Value {
+    fn from(value: generated::ValueReader<'_>) -> Self {
+        match value.to_enum() {
+            generated::ValueUnionReader::Number(v) => Value::Number(read_u16(v)),
+            generated::ValueUnionReader::RandomNumber(v) => Value::Random(v.into()),
+        }
+    }
+}
@@ -1,15 +1,15 @@
-use eyre::Result;
 use molecule::prelude::{Builder, Entity};
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use spore_warriors_generated as generated;
-use std::fs;
-use std::path::PathBuf;
 
+use super::types::{read_u16, read_u16_vec, read_u8};
 use crate::{convert_u16, convert_vec};
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 pub struct Warrior {
     pub id: u16,
+    /// Card ids, each either in `deck_status` or a `special` class card
     pub special_cards: Vec<u16>,
     pub hp: u16,
     pub gold: u16,
@@ -30,7 +30,9 @@ pub struct Warrior {
     pub defense_weak: u8,
     pub physique: u8,
     pub draw_count: u8,
+    /// Starting deck as card ids, at least `draw_count` of them
     pub deck_status: Vec<u16>,
+    /// Starting package as item ids
     pub package_status: Vec<u16>,
 }
 
@@ -64,26 +66,42 @@ impl From<Warrior> for generated::Warrior {
     }
 }
 
-#[derive(Deserialize, Debug)]
-pub struct WarriorPool {
-    #[serde(alias = "warriors")]
-    pub warrior_pool: Vec<Warrior>,
-}
-
-impl WarriorPool {
-    pub fn parse_from(path: PathBuf) -> Result<Self> {
-        let raw_warrior_pool = fs::read_to_string(path)?;
-        Ok(serde_json::from_str(&raw_warrior_pool)?)
+impl From<generated::WarriorReader<'_>> for Warrior {
+    fn from(value: generated::WarriorReader<'_>) -> Self {
+        Self {
+            id: read_u16(value.id()),
+            special_cards: read_u16_vec(value.special_cards()),
+            hp: read_u16(value.hp()),
+            gold: read_u16(value.gold()),
+            power: read_u8(value.power()),
+            motion: read_u8(value.motion()),
+            view_range: read_u8(value.view_range()),
+            armor: read_u8(value.armor()),
+            shield: read_u8(value.shield()),
+            attack: read_u8(value.attack()),
+            attack_weak: read_u8(value.attack_weak()),
+            defense: read_u8(value.defense()),
+            defense_weak: read_u8(value.defense_weak()),
+            physique: read_u8(value.physique()),
+            draw_count: read_u8(value.draw_count()),
+            deck_status: read_u16_vec(value.deck_status()),
+            package_status: read_u16_vec(value.package_status()),
+        }
     }
 }
 
-impl From<WarriorPool> for generated::WarriorVec {
-    fn from(value: WarriorPool) -> Self {
-        let pool = value.warrior_pool;
-        convert_vec!(pool, WarriorVec)
-    }
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Default)]
+pub struct WarriorPool {
+    #[serde(rename = "warriors", alias = "warrior_pool")]
+    pub warrior_pool: Vec<Warrior>,
 }
 
+crate::pool_molecule!(WarriorPool {
+    warrior_pool: Warrior,
+    file: "warriors",
+    molecule: WarriorVec, WarriorVecReader of Warrior,
+});
+
 #[test]
 fn test_parse_warrior_pool() {
     let warrior_pool = WarriorPool::parse_from("./resources/warriors.json".into()).unwrap();
@@ -0,0 +1,207 @@
+use eyre::{Result, WrapErr};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::convert::Infallible;
+use std::fs;
+use std::path::Path;
+
+use crate::loader::{
+    parse_file, visit_pools, PoolVisitor, ResourceFormat, ResourcePoolKind, ResourceSet,
+};
+use crate::manifest::hash_hex;
+use crate::validate::ValidationReport;
+
+/// Subdirectory of the resource directory holding one `<locale>.json` string table per locale
+pub const LOCALES_DIR: &str = "locales";
+
+/// Pools whose entries are shown to players and need a name and description in every locale
+pub const LOCALIZED_POOLS: [&str; 5] = ["cards", "enemies", "warriors", "items", "scenes"];
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct LocalizedText {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flavor: Option<String>,
+}
+
+/// Texts of one locale, keyed by pool file stem then entry id
+pub type LocaleTable = BTreeMap<String, BTreeMap<u16, LocalizedText>>;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct Localization {
+    pub locales: BTreeMap<String, LocaleTable>,
+}
+
+/// What clients download next to `resources.bin`, tied to it by hash
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LocaleBundle {
+    pub resources_hash: String,
+    pub locales: BTreeMap<String, LocaleTable>,
+}
+
+struct LocalizedIds<'a> {
+    resource_set: &'a ResourceSet,
+    ids: BTreeMap<&'static str, BTreeSet<u16>>,
+}
+
+impl PoolVisitor for LocalizedIds<'_> {
+    type Error = Infallible;
+
+    fn visit<P: ResourcePoolKind>(&mut self) -> Result<(), Infallible> {
+        if LOCALIZED_POOLS.contains(&P::FILE_NAME) {
+            let ids = P::of(self.resource_set).entries().iter().map(P::id);
+            self.ids.insert(P::FILE_NAME, ids.collect());
+        }
+        Ok(())
+    }
+}
+
+impl Localization {
+    /// Loads every string table under `<dir>/locales`, an absent directory means no locales
+    pub fn load(dir: &Path) -> Result<Self> {
+        let mut localization = Self::default();
+        let dir = dir.join(LOCALES_DIR);
+        if !dir.is_dir() {
+            return Ok(localization);
+        }
+        let mut files = fs::read_dir(&dir)?
+            .map(|v| Ok(v?.path()))
+            .collect::<Result<Vec<_>>>()?;
+        files.sort();
+        for path in files {
+            if ResourceFormat::from_path(&path).is_err() {
+                continue;
+            }
+            let locale = path
+                .file_stem()
+                .and_then(|v| v.to_str())
+                .unwrap_or_default()
+                .to_owned();
+            let table = parse_file(&path).wrap_err_with(|| format!("load locale {locale}"))?;
+            localization.locales.insert(locale, table);
+        }
+        Ok(localization)
+    }
+
+    /// Reports entries without a name or description, and texts for pools or ids that do
+    /// not exist, in every locale
+    pub fn validate(&self, resource_set: &ResourceSet) -> ValidationReport {
+        let mut localized = LocalizedIds {
+            resource_set,
+            ids: BTreeMap::new(),
+        };
+        let Ok(()) = visit_pools(&mut localized);
+        let mut report = ValidationReport::default();
+        for (locale, table) in &self.locales {
+            for (pool, ids) in &localized.ids {
+                let texts = table.get(*pool);
+                for id in ids {
+                    let text = texts.and_then(|v| v.get(id));
+                    let text = text.cloned().unwrap_or_default();
+                    for (field, value) in [("name", &text.name), ("description", &text.description)]
+                    {
+                        if value.trim().is_empty() {
+                            report.error(pool, *id, field, format!("missing in {locale}"));
+                        }
+                    }
+                }
+            }
+            for (pool, texts) in table {
+                let Some(ids) = localized.ids.get(pool.as_str()) else {
+                    let message = format!("in {locale} belongs to a pool without texts");
+                    report.error(pool, 0, "text", message);
+                    continue;
+                };
+                for id in texts.keys().filter(|v| !ids.contains(v)) {
+                    let message = format!("in {locale} has no matching entry");
+                    report.error(pool, *id, "text", message);
+                }
+            }
+        }
+        report
+    }
+
+    /// Fills the descriptions translators left empty with generated ones, such as the card
+    /// texts rendered from systems, adding locales that have no table yet
+    pub fn fill_descriptions(&mut self, generated: BTreeMap<String, LocaleTable>) {
+        for (locale, table) in generated {
+            let current = self.locales.entry(locale).or_default();
+            for (pool, texts) in table {
+                let current = current.entry(pool).or_default();
+                for (id, text) in texts {
+                    let current = current.entry(id).or_default();
+                    if current.description.trim().is_empty() {
+                        current.description = text.description;
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn bundle(&self, binary: &[u8]) -> LocaleBundle {
+        LocaleBundle {
+            resources_hash: hash_hex(binary),
+            locales: self.locales.clone(),
+        }
+    }
+}
+
+#[test]
+fn test_validate_and_bundle_locales() {
+    let dir = std::env::temp_dir().join("spore-warriors-locales");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join(LOCALES_DIR)).unwrap();
+    let resource_set = ResourceSet::load("./resources".as_ref()).unwrap();
+    assert!(Localization::load(&dir).unwrap().locales.is_empty());
+
+    let mut table = LocaleTable::new();
+    for (pool, ids) in [
+        ("cards", vec![100, 101, 102]),
+        ("enemies", vec![3000, 3001]),
+        ("warriors", vec![5001, 5002]),
+        ("items", vec![1000, 1001, 1002]),
+        ("scenes", vec![7001]),
+    ] {
+        let texts = ids.into_iter().map(|id| {
+            let text = LocalizedText {
+                name: format!("{pool} {id}"),
+                description: format!("{pool} {id} description"),
+                flavor: None,
+            };
+            (id, text)
+        });
+        table.insert(pool.to_owned(), texts.collect());
+    }
+    let mut incomplete = table.clone();
+    incomplete.get_mut("cards").unwrap().remove(&101);
+    incomplete
+        .get_mut("enemies")
+        .unwrap()
+        .insert(3009, LocalizedText::default());
+    fs::write(
+        dir.join(LOCALES_DIR).join("en.json"),
+        serde_json::to_string(&table).unwrap(),
+    )
+    .unwrap();
+    fs::write(
+        dir.join(LOCALES_DIR).join("zh-CN.yaml"),
+        serde_yaml::to_string(&incomplete).unwrap(),
+    )
+    .unwrap();
+
+    let localization = Localization::load(&dir).unwrap();
+    assert_eq!(localization.locales.len(), 2);
+    assert_eq!(
+        localization.validate(&resource_set).to_string(),
+        "cards 101: name missing in zh-CN\n\
+         cards 101: description missing in zh-CN\n\
+         enemies 3009: text in zh-CN has no matching entry\n"
+    );
+    let binary = resource_set.into_binary();
+    let bundle = localization.bundle(&binary);
+    assert_eq!(bundle.resources_hash, hash_hex(&binary));
+    assert_eq!(bundle.locales["en"], table);
+}
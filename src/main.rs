@@ -1,36 +1,361 @@
-use molecule::prelude::{Builder, Entity};
-use spore_warriors_generated as generated;
+use eyre::{bail, Result};
+use spore_warriors_resources::canonical::format_resources;
+use spore_warriors_resources::diff::diff;
+use spore_warriors_resources::difficulty::{DifficultyReport, DEFAULT_TOLERANCE};
+use spore_warriors_resources::loader::{EnemyRank, ResourceSet, RESOURCE_FILES};
+use spore_warriors_resources::locale::Localization;
+use spore_warriors_resources::manifest::Manifest;
+use spore_warriors_resources::patch::{apply_patch, create_patch, ResourcePatch};
+use spore_warriors_resources::ramp::RampReport;
+use spore_warriors_resources::schema::write_schemas;
+use spore_warriors_resources::size::{SizeBudget, SizeReport};
+use spore_warriors_resources::split::write_cells;
+use spore_warriors_resources::spreadsheet::{export_pool, import_pool};
+use spore_warriors_resources::text::{TextRenderer, TextTemplates};
+use spore_warriors_resources::validate::{validate_dir, ValidationReport};
+use spore_warriors_resources::watch::ResourceWatcher;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
 
-mod loader;
-use loader::*;
+fn option_value<'a>(args: &'a [String], name: &str) -> Option<&'a String> {
+    let position = args.iter().position(|v| v == name)?;
+    args.get(position + 1)
+}
+
+fn option_values<'a>(args: &'a [String], name: &'a str) -> impl Iterator<Item = &'a String> {
+    args.windows(2).filter(move |v| v[0] == name).map(|v| &v[1])
+}
+
+fn check_budget(args: &[String], report: &SizeReport) -> Result<()> {
+    match option_value(args, "--budget") {
+        Some(path) => SizeBudget::parse_from(Path::new(path))?.check(report),
+        None => Ok(()),
+    }
+}
+
+/// Fails on validation errors, warnings are printed and otherwise let through
+fn check_validation(report: ValidationReport) -> Result<()> {
+    if report.is_empty() {
+        for warning in &report.warnings {
+            eprintln!("warning: {warning}");
+        }
+    }
+    report.into_result()
+}
+
+fn build(args: &[String]) -> Result<()> {
+    let dir = Path::new("./resources");
+    let overlays = option_values(args, "--overlay")
+        .map(PathBuf::from)
+        .collect::<Vec<_>>();
+    let (resource_set, report) = ResourceSet::load_with_overlays(dir, &overlays)?;
+    for conflict in &report.conflicts {
+        eprintln!("overlay conflict: {conflict}");
+    }
+    check_validation(validate_dir(dir, &resource_set)?)?;
+    let binary = resource_set.into_binary();
+    check_budget(args, &SizeReport::new(&binary)?)?;
+    if let Some(out_dir) = option_value(args, "--split") {
+        write_cells(&binary, Path::new(out_dir))?;
+        return Ok(());
+    }
+    let mut sources = ResourceSet::source_files(dir)?;
+    for overlay in &overlays {
+        sources.extend(ResourceSet::source_files(overlay)?);
+    }
+    write_outputs(binary, &sources)
+}
 
-macro_rules! json_parse {
-    ($pool:ty, $name:expr) => {
-        <$pool>::parse_from(format!("./resources/{}.json", $name).into())
-            .expect(format!("load {}.json", $name).as_str())
+fn write_outputs(binary: Vec<u8>, sources: &[PathBuf]) -> Result<()> {
+    let manifest = Manifest::new(&binary, sources)?;
+    std::fs::write("./resources.bin", binary)?;
+    std::fs::write(
+        "./resources.manifest.json",
+        serde_json::to_string_pretty(&manifest)?,
+    )?;
+    Ok(())
+}
+
+/// Reloads the `pending` pools and writes the outputs once every pool parses and validates.
+/// A pool that fails to parse stays pending, its stale contents must never be written
+fn rebuild(
+    dir: &Path,
+    cached: &mut Option<ResourceSet>,
+    pending: &mut BTreeSet<&'static str>,
+) -> Result<usize> {
+    let resource_set = match cached {
+        Some(resource_set) => {
+            let mut errors = vec![];
+            let pools = RESOURCE_FILES.into_iter().filter(|v| pending.contains(v));
+            for name in pools.collect::<Vec<_>>() {
+                match resource_set.reload_pool(dir, name) {
+                    Ok(()) => {
+                        pending.remove(name);
+                    }
+                    Err(err) => errors.push(format!("{err:#}")),
+                }
+            }
+            if !errors.is_empty() {
+                bail!("{}", errors.join("\n"));
+            }
+            resource_set
+        }
+        None => cached.insert(ResourceSet::load(dir)?),
+    };
+    // every pool is loaded now and configs are read by `validate_dir` on each rebuild
+    pending.clear();
+    check_validation(validate_dir(dir, resource_set)?)?;
+    let binary = resource_set.clone().into_binary();
+    let size = binary.len();
+    write_outputs(binary, &ResourceSet::source_files(dir)?)?;
+    Ok(size)
+}
+
+fn watch_command(args: &[String]) -> Result<()> {
+    let dir = Path::new("./resources");
+    let interval = match option_value(args, "--interval") {
+        Some(ms) => Duration::from_millis(ms.parse()?),
+        None => Duration::from_millis(500),
     };
+    let mut watcher = ResourceWatcher::new(dir);
+    let mut resource_set = None;
+    let mut pending = RESOURCE_FILES.into_iter().collect::<BTreeSet<_>>();
+    let mut failed = None;
+    loop {
+        pending.extend(watcher.changed_sources());
+        // a pool that failed to parse stays pending and is retried on every tick
+        if !pending.is_empty() {
+            let names = pending.iter().copied().collect::<Vec<_>>().join(", ");
+            match rebuild(dir, &mut resource_set, &mut pending) {
+                Ok(size) => {
+                    println!("[{names}] rebuilt resources.bin, {size} bytes");
+                    failed = None;
+                }
+                Err(err) => {
+                    let message = format!("{err:#}");
+                    if failed.as_ref() != Some(&message) {
+                        eprintln!("[{names}] {message}");
+                    }
+                    failed = Some(message);
+                }
+            }
+        }
+        thread::sleep(interval);
+    }
+}
+
+fn fmt_command(args: &[String]) -> Result<()> {
+    let dir = args
+        .first()
+        .filter(|v| !v.starts_with("--"))
+        .map(String::as_str)
+        .unwrap_or("./resources");
+    let files = format_resources(Path::new(dir))?;
+    let changed = files.iter().filter(|v| v.changed).collect::<Vec<_>>();
+    if args.iter().any(|v| v == "--check") {
+        for file in &changed {
+            println!("{} is not canonical", file.path.display());
+        }
+        if !changed.is_empty() {
+            bail!(
+                "{} files need formatting, run `fmt` to rewrite them",
+                changed.len()
+            );
+        }
+        return Ok(());
+    }
+    for file in changed {
+        std::fs::write(&file.path, &file.content)?;
+        println!("formatted {}", file.path.display());
+    }
+    Ok(())
+}
+
+fn locale_command(args: &[String]) -> Result<()> {
+    let dir = Path::new("./resources");
+    let resource_set = ResourceSet::load(dir)?;
+    let mut localization = Localization::load(dir)?;
+    let templates = TextTemplates::load(dir)?;
+    localization.fill_descriptions(
+        TextRenderer::new(&resource_set, &templates).descriptions(&resource_set)?,
+    );
+    localization.validate(&resource_set).into_result()?;
+    match (args.first().map(String::as_str), args.get(1)) {
+        (Some("check"), None) => {
+            println!("{} locales complete", localization.locales.len());
+        }
+        (Some("bundle"), Some(out)) => {
+            let bundle = localization.bundle(&resource_set.into_binary());
+            std::fs::write(out, serde_json::to_string(&bundle)?)?;
+        }
+        _ => bail!("usage: locale check | locale bundle <out.json>"),
+    }
+    Ok(())
+}
+
+fn text_command(args: &[String]) -> Result<()> {
+    let Some(locale) = args.first() else {
+        bail!("usage: text <locale>");
+    };
+    let dir = Path::new("./resources");
+    let resource_set = ResourceSet::load(dir)?;
+    let templates = TextTemplates::load(dir)?;
+    let renderer = TextRenderer::new(&resource_set, &templates);
+    for card in &resource_set.card_pool.card_pool {
+        println!(
+            "Card {}: {}",
+            card.id,
+            renderer.render(locale, &card.system_pool)?
+        );
+    }
+    for item in &resource_set.item_pool.item_pool {
+        println!(
+            "Item {}: {}",
+            item.id,
+            renderer.render(locale, &item.system_pool)?
+        );
+    }
+    Ok(())
+}
+
+fn difficulty_command(args: &[String]) -> Result<()> {
+    let resource_set = ResourceSet::load(Path::new("./resources"))?;
+    // ranks are named by code only, so their order has to come from whoever knows it
+    let rank_order = option_values(args, "--rank")
+        .map(|v| serde_json::from_value(serde_json::Value::String(v.clone())))
+        .collect::<Result<Vec<EnemyRank>, _>>()?;
+    let report = DifficultyReport::new(&resource_set, &rank_order);
+    if args.iter().any(|v| v == "--json") {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print!("{report}");
+    }
+    if !args.iter().any(|v| v == "--check") {
+        return Ok(());
+    }
+    if rank_order.len() < 2 {
+        bail!("--check needs at least two --rank values, from weakest to strongest");
+    }
+    let tolerance = match option_value(args, "--tolerance") {
+        Some(v) => v.parse::<f64>()?,
+        None => DEFAULT_TOLERANCE,
+    };
+    let allowed = option_values(args, "--allow")
+        .map(|v| v.parse::<u16>())
+        .collect::<Result<Vec<_>, _>>()?;
+    let violations = report.violations(tolerance, &allowed);
+    if !violations.is_empty() {
+        let violations = violations.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        bail!(
+            "{} lower ranked enemies rate more than {:.0}% above a higher ranked one:\n{}",
+            violations.len(),
+            tolerance * 100.0,
+            violations.join("\n")
+        );
+    }
+    Ok(())
+}
+
+fn ramp_command(args: &[String]) -> Result<()> {
+    let resource_set = ResourceSet::load(Path::new("./resources"))?;
+    let report = RampReport::new(&resource_set);
+    if args.iter().any(|v| v == "--json") {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print!("{report}");
+    }
+    Ok(())
+}
+
+fn diff_command(args: &[String]) -> Result<()> {
+    let (Some(old), Some(new)) = (args.first(), args.get(1)) else {
+        bail!("usage: diff <old> <new> [--json]");
+    };
+    let old = ResourceSet::open(Path::new(old))?;
+    let new = ResourceSet::open(Path::new(new))?;
+    let resource_diff = diff(&old, &new);
+    if args.iter().any(|v| v == "--json") {
+        println!("{}", serde_json::to_string_pretty(&resource_diff)?);
+    } else {
+        print!("{resource_diff}");
+    }
+    Ok(())
+}
+
+fn size_command(args: &[String]) -> Result<()> {
+    let path = args
+        .first()
+        .filter(|v| !v.starts_with("--"))
+        .map(String::as_str)
+        .unwrap_or("./resources");
+    let binary = ResourceSet::open(Path::new(path))?.into_binary();
+    let report = SizeReport::new(&binary)?;
+    if args.iter().any(|v| v == "--json") {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print!("{report}");
+    }
+    check_budget(args, &report)
+}
+
+fn patch_command(args: &[String]) -> Result<()> {
+    match (args.first().map(String::as_str), args.get(1), args.get(2), args.get(3)) {
+        (Some("create"), Some(old), Some(new), Some(out)) => {
+            let old = ResourceSet::open(Path::new(old))?.into_binary();
+            let new = ResourceSet::open(Path::new(new))?.into_binary();
+            let patch = create_patch(&old, &new)?;
+            std::fs::write(out, serde_json::to_string_pretty(&patch)?)?;
+        }
+        (Some("apply"), Some(old), Some(patch), Some(out)) => {
+            let old = ResourceSet::open(Path::new(old))?.into_binary();
+            let patch: ResourcePatch = serde_json::from_str(&std::fs::read_to_string(patch)?)?;
+            std::fs::write(out, apply_patch(&old, &patch)?)?;
+        }
+        _ => bail!("usage: patch create <old> <new> <patch.json> | patch apply <old> <patch.json> <out.bin>"),
+    }
+    Ok(())
+}
+
+fn csv_command(args: &[String]) -> Result<()> {
+    match (
+        args.first().map(String::as_str),
+        args.get(1),
+        args.get(2),
+        args.get(3),
+    ) {
+        (Some("export"), Some(pool), Some(out), None) => {
+            let resource_set = ResourceSet::load(Path::new("./resources"))?;
+            std::fs::write(out, export_pool(&resource_set, pool)?)?;
+        }
+        (Some("import"), Some(pool), Some(sheet), Some(out)) => {
+            let document = import_pool(pool, &std::fs::read_to_string(sheet)?)?;
+            std::fs::write(out, serde_json::to_string_pretty(&document)? + "\n")?;
+        }
+        _ => bail!("usage: csv export <pool> <out.csv> | csv import <pool> <in.csv> <out.json>"),
+    }
+    Ok(())
 }
 
-fn main() {
-    let action_pool = json_parse!(ActionPool, "actions");
-    let card_pool = json_parse!(CardPool, "cards");
-    let system_pool = json_parse!(SystemPool, "systems");
-    let enemy_pool = json_parse!(EnemyPool, "enemies");
-    let loot_pool = json_parse!(LootPool, "loots");
-    let scene_pool = json_parse!(ScenePool, "scenes");
-    let warrior_pool = json_parse!(WarriorPool, "warriors");
-    let item_pool = json_parse!(ItemPool, "items");
-
-    let resource_pool = generated::ResourcePool::new_builder()
-        .action_pool(action_pool.into())
-        .card_pool(card_pool.into())
-        .system_pool(system_pool.into())
-        .enemy_pool(enemy_pool.into())
-        .loot_pool(loot_pool.into())
-        .scene_pool(scene_pool.into())
-        .warrior_pool(warrior_pool.into())
-        .item_pool(item_pool.into())
-        .build();
-
-    std::fs::write("./resources.bin", resource_pool.as_bytes()).expect("persistence");
+fn main() -> Result<()> {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    match args.first().map(String::as_str) {
+        None => build(&[]),
+        Some("build") => build(&args[1..]),
+        Some("diff") => diff_command(&args[1..]),
+        Some("difficulty") => difficulty_command(&args[1..]),
+        Some("fmt") => fmt_command(&args[1..]),
+        Some("locale") => locale_command(&args[1..]),
+        Some("size") => size_command(&args[1..]),
+        Some("patch") => patch_command(&args[1..]),
+        Some("ramp") => ramp_command(&args[1..]),
+        Some("csv") => csv_command(&args[1..]),
+        Some("text") => text_command(&args[1..]),
+        Some("watch") => watch_command(&args[1..]),
+        Some("schema") => write_schemas(Path::new(
+            args.get(1).map(String::as_str).unwrap_or("./schemas"),
+        )),
+        Some(command) => bail!("unknown command `{command}`"),
+    }
 }
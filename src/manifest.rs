@@ -0,0 +1,123 @@
+use blake2b_rs::Blake2bBuilder;
+use eyre::{eyre, Result, WrapErr};
+use molecule::prelude::Reader;
+use serde::{Deserialize, Serialize};
+use spore_warriors_generated as generated;
+use std::convert::Infallible;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::loader::{visit_pools, PoolVisitor, ResourcePoolKind};
+
+pub const CKB_HASH_PERSONALIZATION: &[u8] = b"ckb-default-hash";
+
+/// The blake2b-256 hash CKB uses for cell data
+pub fn blake2b_256(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Blake2bBuilder::new(32)
+        .personal(CKB_HASH_PERSONALIZATION)
+        .build();
+    hasher.update(data);
+    let mut hash = [0u8; 32];
+    hasher.finalize(&mut hash);
+    hash
+}
+
+pub fn hash_hex(data: &[u8]) -> String {
+    format!("0x{}", hex::encode(blake2b_256(data)))
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PoolManifest {
+    pub name: String,
+    pub count: usize,
+    pub size: usize,
+    pub hash: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SourceManifest {
+    pub path: String,
+    pub hash: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Manifest {
+    pub hash: String,
+    pub size: usize,
+    pub pools: Vec<PoolManifest>,
+    pub sources: Vec<SourceManifest>,
+}
+
+struct ManifestVisitor<'r> {
+    reader: generated::ResourcePoolReader<'r>,
+    pools: Vec<PoolManifest>,
+}
+
+impl PoolVisitor for ManifestVisitor<'_> {
+    type Error = Infallible;
+
+    fn visit<P: ResourcePoolKind>(&mut self) -> Result<(), Infallible> {
+        let data = P::pool_slice(self.reader);
+        self.pools.push(PoolManifest {
+            name: P::FIELD_NAME.to_owned(),
+            count: P::entry_slices(self.reader).len(),
+            size: data.len(),
+            hash: hash_hex(data),
+        });
+        Ok(())
+    }
+}
+
+impl Manifest {
+    pub fn new(binary: &[u8], sources: &[PathBuf]) -> Result<Self> {
+        let reader = generated::ResourcePoolReader::from_slice(binary)
+            .map_err(|err| eyre!("invalid resource binary: {err}"))?;
+        let mut visitor = ManifestVisitor {
+            reader,
+            pools: vec![],
+        };
+        let Ok(()) = visit_pools(&mut visitor);
+        let sources = sources
+            .iter()
+            .map(|path| {
+                let raw = fs::read(path).wrap_err_with(|| format!("read {}", path.display()))?;
+                Ok(SourceManifest {
+                    path: path.display().to_string(),
+                    hash: hash_hex(&raw),
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            hash: hash_hex(binary),
+            size: binary.len(),
+            pools: visitor.pools,
+            sources,
+        })
+    }
+}
+
+#[test]
+fn test_ckb_blake2b_empty() {
+    assert_eq!(
+        hash_hex(&[]),
+        "0x44f4c69744d5f8c55d642062949dcae49bc4e7ef43d388c5a12f42b5633d163e"
+    );
+}
+
+#[test]
+fn test_generate_manifest() {
+    use crate::loader::ResourceSet;
+    let dir = std::path::Path::new("./resources");
+    let resource_set = ResourceSet::load(dir).unwrap();
+    let card_count = resource_set.card_pool.card_pool.len();
+    let binary = resource_set.into_binary();
+    let sources = ResourceSet::source_files(dir).unwrap();
+    let manifest = Manifest::new(&binary, &sources).unwrap();
+    println!(
+        "[MANIFEST] {}",
+        serde_json::to_string_pretty(&manifest).unwrap()
+    );
+    assert_eq!(manifest.hash, hash_hex(&binary));
+    assert_eq!(manifest.pools[1].count, card_count);
+    assert_eq!(manifest.sources.len(), 8);
+}
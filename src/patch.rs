@@ -0,0 +1,219 @@
+use eyre::{eyre, Result, WrapErr};
+use molecule::prelude::{Builder, Entity, Reader};
+use serde::{Deserialize, Serialize};
+use spore_warriors_generated as generated;
+use std::collections::BTreeMap;
+use std::mem;
+
+use crate::loader::{visit_pools, PoolVisitor, ResourcePoolKind};
+use crate::manifest::hash_hex;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PatchEntry {
+    pub id: u16,
+    pub data: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct PoolPatch {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub upsert: Vec<PatchEntry>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub remove: Vec<u16>,
+    /// Final id order, only present when it differs from the in-place replacement order
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order: Option<Vec<u16>>,
+}
+
+impl PoolPatch {
+    pub fn is_empty(&self) -> bool {
+        self.upsert.is_empty() && self.remove.is_empty() && self.order.is_none()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ResourcePatch {
+    pub old_hash: String,
+    pub new_hash: String,
+    pub pools: Vec<PoolPatch>,
+}
+
+type Entries<'r> = Vec<(u16, &'r [u8])>;
+
+fn index_entries<'r>(name: &str, entries: &Entries<'r>) -> Result<BTreeMap<u16, &'r [u8]>> {
+    let mut index = BTreeMap::new();
+    for (id, data) in entries {
+        if index.insert(*id, *data).is_some() {
+            return Err(eyre!("duplicate id {id} in {name}"));
+        }
+    }
+    Ok(index)
+}
+
+fn apply_order(old: &Entries, patch: &PoolPatch) -> Vec<u16> {
+    if let Some(order) = &patch.order {
+        return order.clone();
+    }
+    let mut order = old
+        .iter()
+        .map(|(id, _)| *id)
+        .filter(|id| !patch.remove.contains(id))
+        .collect::<Vec<_>>();
+    for entry in &patch.upsert {
+        if !order.contains(&entry.id) {
+            order.push(entry.id);
+        }
+    }
+    order
+}
+
+fn diff_pool(name: &str, old: &Entries, new: &Entries) -> Result<PoolPatch> {
+    let old_index = index_entries(name, old)?;
+    let new_index = index_entries(name, new)?;
+    let mut patch = PoolPatch {
+        name: name.to_owned(),
+        ..Default::default()
+    };
+    patch.upsert = new
+        .iter()
+        .filter(|(id, data)| old_index.get(id) != Some(data))
+        .map(|(id, data)| PatchEntry {
+            id: *id,
+            data: format!("0x{}", hex::encode(data)),
+        })
+        .collect();
+    patch.remove = old
+        .iter()
+        .map(|(id, _)| *id)
+        .filter(|id| !new_index.contains_key(id))
+        .collect();
+    let new_order = new.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+    if apply_order(old, &patch) != new_order {
+        patch.order = Some(new_order);
+    }
+    Ok(patch)
+}
+
+fn apply_pool(name: &str, old: &Entries, patch: Option<&PoolPatch>) -> Result<Vec<Vec<u8>>> {
+    let Some(patch) = patch else {
+        return Ok(old.iter().map(|(_, data)| data.to_vec()).collect());
+    };
+    let mut index = index_entries(name, old)?
+        .into_iter()
+        .map(|(id, data)| (id, data.to_vec()))
+        .collect::<BTreeMap<_, _>>();
+    for id in &patch.remove {
+        index
+            .remove(id)
+            .ok_or_else(|| eyre!("cannot remove missing id {id} from {name}"))?;
+    }
+    for entry in &patch.upsert {
+        let data = hex::decode(entry.data.trim_start_matches("0x"))?;
+        index.insert(entry.id, data);
+    }
+    apply_order(old, patch)
+        .into_iter()
+        .map(|id| {
+            index
+                .remove(&id)
+                .ok_or_else(|| eyre!("patched {name} has no entry for id {id}"))
+        })
+        .collect()
+}
+
+struct CreateVisitor<'r> {
+    old: generated::ResourcePoolReader<'r>,
+    new: generated::ResourcePoolReader<'r>,
+    pools: Vec<PoolPatch>,
+}
+
+impl PoolVisitor for CreateVisitor<'_> {
+    type Error = eyre::Report;
+
+    fn visit<P: ResourcePoolKind>(&mut self) -> Result<()> {
+        let old = P::entry_slices(self.old);
+        let pool = diff_pool(P::FIELD_NAME, &old, &P::entry_slices(self.new))?;
+        if !pool.is_empty() {
+            self.pools.push(pool);
+        }
+        Ok(())
+    }
+}
+
+/// Computes the per-pool entries to add, replace or remove to turn `old` into `new`
+pub fn create_patch(old: &[u8], new: &[u8]) -> Result<ResourcePatch> {
+    let mut visitor = CreateVisitor {
+        old: generated::ResourcePoolReader::from_slice(old)
+            .map_err(|err| eyre!("invalid old resource binary: {err}"))?,
+        new: generated::ResourcePoolReader::from_slice(new)
+            .map_err(|err| eyre!("invalid new resource binary: {err}"))?,
+        pools: vec![],
+    };
+    visit_pools(&mut visitor)?;
+    Ok(ResourcePatch {
+        old_hash: hash_hex(old),
+        new_hash: hash_hex(new),
+        pools: visitor.pools,
+    })
+}
+
+struct ApplyVisitor<'r> {
+    old: generated::ResourcePoolReader<'r>,
+    patch: &'r ResourcePatch,
+    builder: generated::ResourcePoolBuilder,
+}
+
+impl PoolVisitor for ApplyVisitor<'_> {
+    type Error = eyre::Report;
+
+    fn visit<P: ResourcePoolKind>(&mut self) -> Result<()> {
+        let name = P::FIELD_NAME;
+        let pool_patch = self.patch.pools.iter().find(|v| v.name == name);
+        let entries = apply_pool(name, &P::entry_slices(self.old), pool_patch)?;
+        let molecule = P::molecule_from_entries(&entries)
+            .wrap_err_with(|| format!("apply patch to {name}"))?;
+        self.builder = P::set_molecule(mem::take(&mut self.builder), molecule);
+        Ok(())
+    }
+}
+
+/// Rebuilds the new `ResourcePool` binary from the old one, verified against the patch hashes
+pub fn apply_patch(old: &[u8], patch: &ResourcePatch) -> Result<Vec<u8>> {
+    if hash_hex(old) != patch.old_hash {
+        return Err(eyre!("patch does not apply to resource {}", hash_hex(old)));
+    }
+    let mut visitor = ApplyVisitor {
+        old: generated::ResourcePoolReader::from_slice(old)
+            .map_err(|err| eyre!("invalid resource binary: {err}"))?,
+        patch,
+        builder: generated::ResourcePool::new_builder(),
+    };
+    visit_pools(&mut visitor)?;
+    let binary = visitor.builder.build().as_bytes().to_vec();
+    if hash_hex(&binary) != patch.new_hash {
+        return Err(eyre!("patched resource hash mismatch"));
+    }
+    Ok(binary)
+}
+
+#[test]
+fn test_create_and_apply_patch() {
+    use crate::loader::ResourceSet;
+    let old_set = ResourceSet::load("./resources".as_ref()).unwrap();
+    let mut new_set = old_set.clone();
+    new_set.card_pool.card_pool[0].power_cost += 1;
+    new_set.enemy_pool.enemy_pool.remove(0);
+    new_set.item_pool.item_pool.reverse();
+    let old = old_set.into_binary();
+    let new = new_set.into_binary();
+
+    let patch = create_patch(&old, &new).unwrap();
+    println!("[PATCH] {}", serde_json::to_string(&patch).unwrap());
+    assert_eq!(patch.pools.len(), 3);
+    assert_eq!(patch.pools[0].upsert.len(), 1);
+    assert_eq!(patch.pools[1].remove, vec![3000]);
+    assert!(patch.pools[2].upsert.is_empty());
+    assert_eq!(apply_patch(&old, &patch).unwrap(), new);
+    assert!(apply_patch(&new, &patch).is_err());
+}
@@ -0,0 +1,246 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::difficulty::{mean, node_pool_weight, random_mean, rate_enemy};
+use crate::loader::{
+    Coordinate, FixedLevelNode, LevelNode, NodeInstance, ResourcePoolKind, ResourceSet,
+    ScenePartition,
+};
+
+/// Expected content of one partition, fixed nodes inside its area are counted once and
+/// each generated node is drawn uniformly from the node pool
+#[derive(Serialize, Debug, Clone, PartialEq, Default)]
+pub struct PartitionStep {
+    pub start_y: u8,
+    pub end_y: u8,
+    pub nodes: f64,
+    pub difficulty: f64,
+    /// Sum of `recover_point` amounts
+    pub recovery: f64,
+    pub campsites: f64,
+    /// Gold from enemy loots plus the price of items picked from treasure chests
+    pub rewards: f64,
+    /// Cards and items on sale at merchants
+    pub offers: f64,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct SceneRamp {
+    pub scene: u16,
+    pub steps: Vec<PartitionStep>,
+    /// Fixed nodes outside every partition, left out of the steps
+    pub unassigned: Vec<Coordinate>,
+    /// Whether expected difficulty never drops from one partition to the next
+    pub smooth: bool,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Default)]
+pub struct RampReport {
+    pub scenes: Vec<SceneRamp>,
+}
+
+struct Ramp<'a> {
+    resource_set: &'a ResourceSet,
+    difficulties: BTreeMap<u16, f64>,
+}
+
+impl Ramp<'_> {
+    fn enemy_gold(&self, id: u16) -> f64 {
+        let Some(enemy) = self.resource_set.enemy_pool.find(id) else {
+            return 0.0;
+        };
+        let loots = &self.resource_set.loot_pool;
+        mean(
+            enemy
+                .loot_pool
+                .iter()
+                .filter_map(|v| loots.find(*v))
+                .map(|v| random_mean(&v.gold)),
+        )
+    }
+
+    fn item_price(&self, id: u16) -> Option<f64> {
+        let item = self.resource_set.item_pool.find(id)?;
+        Some(random_mean(&item.price))
+    }
+
+    /// Adds `weight` times the content of `node` to `step`
+    fn add_node(&self, step: &mut PartitionStep, node: &LevelNode, weight: f64) {
+        step.nodes += weight;
+        match &node.instance {
+            NodeInstance::Enemy(v) => {
+                let count = v.count as f64 * weight;
+                let enemies = || {
+                    v.enemy_pool
+                        .iter()
+                        .filter(|v| self.difficulties.contains_key(v))
+                };
+                step.difficulty += count * mean(enemies().map(|v| self.difficulties[v]));
+                step.rewards += count * mean(enemies().map(|v| self.enemy_gold(*v)));
+            }
+            NodeInstance::TreasureChest(v) => {
+                let price = mean(v.item_pool.iter().filter_map(|v| self.item_price(*v)));
+                step.rewards += v.count as f64 * v.pick as f64 * price * weight;
+            }
+            NodeInstance::RecoverPoint(v) => step.recovery += *v as f64 * weight,
+            NodeInstance::Campsite(_) => step.campsites += weight,
+            NodeInstance::ItemMerchant(v) => step.offers += v.count as f64 * weight,
+            NodeInstance::CardMerchant(v) => step.offers += v.count as f64 * weight,
+            _ => {}
+        }
+    }
+
+    fn step(&self, partition: &ScenePartition, fixed_nodes: &[&FixedLevelNode]) -> PartitionStep {
+        let mut step = PartitionStep {
+            start_y: partition.start_point.y,
+            end_y: partition.end_point.y,
+            ..Default::default()
+        };
+        for fixed in fixed_nodes {
+            self.add_node(&mut step, &fixed.node, 1.0);
+        }
+        let weight = node_pool_weight(partition);
+        for node in &partition.node_pool {
+            self.add_node(&mut step, node, weight);
+        }
+        step
+    }
+}
+
+/// Whether `point` lies in the area of `partition`, both corners included
+fn contains(partition: &ScenePartition, point: &Coordinate) -> bool {
+    let (start, end) = (&partition.start_point, &partition.end_point);
+    (start.x..=end.x).contains(&point.x) && (start.y..=end.y).contains(&point.y)
+}
+
+impl RampReport {
+    pub fn new(resource_set: &ResourceSet) -> Self {
+        let ramp = Ramp {
+            resource_set,
+            difficulties: resource_set
+                .enemy_pool
+                .enemy_pool
+                .iter()
+                .map(|v| (v.id, rate_enemy(v, resource_set).difficulty))
+                .collect(),
+        };
+        let scenes = resource_set
+            .scene_pool
+            .scene_pool
+            .iter()
+            .map(|scene| {
+                let mut partitions = scene.partition_list.iter().collect::<Vec<_>>();
+                partitions.sort_by_key(|v| (v.start_point.y, v.start_point.x));
+                // partitions may share their boundary rows, a fixed node on one belongs to
+                // the first partition holding it so it is counted exactly once
+                let mut fixed_nodes = vec![vec![]; partitions.len()];
+                let mut unassigned = vec![];
+                for fixed in &scene.fixed_nodes {
+                    match partitions.iter().position(|v| contains(v, &fixed.point)) {
+                        Some(i) => fixed_nodes[i].push(fixed),
+                        None => unassigned.push(fixed.point.clone()),
+                    }
+                }
+                let steps = partitions
+                    .into_iter()
+                    .zip(&fixed_nodes)
+                    .map(|(partition, fixed)| ramp.step(partition, fixed))
+                    .collect::<Vec<_>>();
+                let smooth = steps.windows(2).all(|v| v[0].difficulty <= v[1].difficulty);
+                SceneRamp {
+                    scene: scene.id,
+                    steps,
+                    unassigned,
+                    smooth,
+                }
+            })
+            .collect();
+        Self { scenes }
+    }
+}
+
+impl fmt::Display for RampReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for scene in &self.scenes {
+            let shape = if scene.smooth { "ramps up" } else { "has dips" };
+            writeln!(f, "Scene {} ({shape}):", scene.scene)?;
+            writeln!(
+                f,
+                "    {:>9}  {:>5}  {:>10}  {:>8}  {:>9}  {:>7}  {:>6}",
+                "rows", "nodes", "difficulty", "recovery", "campsites", "rewards", "offers"
+            )?;
+            for step in &scene.steps {
+                writeln!(
+                    f,
+                    "    {:>9}  {:>5.1}  {:>10.1}  {:>8.1}  {:>9.1}  {:>7.1}  {:>6.1}",
+                    format!("{}..{}", step.start_y, step.end_y),
+                    step.nodes,
+                    step.difficulty,
+                    step.recovery,
+                    step.campsites,
+                    step.rewards,
+                    step.offers
+                )?;
+            }
+            if !scene.unassigned.is_empty() {
+                let points = scene
+                    .unassigned
+                    .iter()
+                    .map(|v| format!("({}, {})", v.x, v.y))
+                    .collect::<Vec<_>>();
+                writeln!(f, "    outside every partition: {}", points.join(", "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_ramp_report() {
+    use crate::loader::NodeEnemy;
+
+    let mut resource_set = ResourceSet::load("./resources".as_ref()).unwrap();
+    let report = RampReport::new(&resource_set);
+    let steps = &report.scenes[0].steps;
+    assert_eq!(steps.len(), 2);
+    // the starting point is fixed in the first partition, 1.5 nodes are generated in each
+    assert_eq!((steps[0].start_y, steps[0].nodes), (0, 2.5));
+    assert_eq!((steps[1].start_y, steps[1].nodes), (20, 1.5));
+    assert_eq!(steps[1].recovery, 0.75 * 50.0);
+    assert_eq!(steps[1].offers, 0.75 * 3.0);
+    assert!(report.scenes[0].smooth);
+    assert!(report.scenes[0].unassigned.is_empty());
+
+    // partitions span 0..=20 and 20..=40 over x 0..=34: the shared row goes to the first,
+    // the last row is kept and points past either axis are reported
+    let scene = &mut resource_set.scene_pool.scene_pool[0];
+    let start = scene.fixed_nodes[0].clone();
+    for (x, y) in [(10, 20), (34, 40), (35, 10), (0, 41)] {
+        let mut fixed = start.clone();
+        fixed.point = Coordinate { x, y };
+        scene.fixed_nodes.push(fixed);
+    }
+    let report = RampReport::new(&resource_set);
+    let steps = &report.scenes[0].steps;
+    assert_eq!((steps[0].nodes, steps[1].nodes), (3.5, 2.5));
+    assert_eq!(
+        report.scenes[0].unassigned,
+        [Coordinate { x: 35, y: 10 }, Coordinate { x: 0, y: 41 }]
+    );
+    resource_set.scene_pool.scene_pool[0]
+        .fixed_nodes
+        .truncate(1);
+
+    resource_set.scene_pool.scene_pool[0].partition_list[0].node_pool[0].instance =
+        NodeInstance::Enemy(NodeEnemy {
+            count: 1,
+            enemy_pool: vec![3000],
+        });
+    let report = RampReport::new(&resource_set);
+    let first = &report.scenes[0].steps[0];
+    assert_eq!(first.difficulty, 0.75 * 1875f64.sqrt());
+    // enemy 3000 drops one of loots 2001, 2002 and 2003, averaging 95 gold
+    assert_eq!(first.rewards, 0.75 * 95.0);
+    assert!(!report.scenes[0].smooth);
+}
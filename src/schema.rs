@@ -0,0 +1,138 @@
+use eyre::Result;
+use schemars::schema_for;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::loader::{
+    ActionPool, CardPool, EnemyPool, ItemPool, LootPool, ScenePool, SystemPool, WarriorPool,
+    EXTENDS_KEY, RESOURCE_FILES, TEMPLATES_KEY,
+};
+
+/// JSON Schemas of every resource file, keyed by the file stem in `RESOURCE_FILES` order
+pub fn pool_schemas() -> Vec<(&'static str, Value)> {
+    let schemas = [
+        schema_for!(ActionPool),
+        schema_for!(CardPool),
+        schema_for!(SystemPool),
+        schema_for!(EnemyPool),
+        schema_for!(LootPool),
+        schema_for!(ScenePool),
+        schema_for!(WarriorPool),
+        schema_for!(ItemPool),
+    ];
+    RESOURCE_FILES
+        .into_iter()
+        .zip(schemas)
+        .map(|(name, schema)| {
+            let mut schema = serde_json::to_value(schema).expect("serialize schema");
+            annotate(&mut schema);
+            allow_templates(&mut schema);
+            (name, schema)
+        })
+        .collect()
+}
+
+pub fn schema_path(dir: &Path, name: &str) -> PathBuf {
+    dir.join(format!("{name}.schema.json"))
+}
+
+pub fn write_schemas(out_dir: &Path) -> Result<()> {
+    fs::create_dir_all(out_dir)?;
+    for (name, schema) in pool_schemas() {
+        let content = serde_json::to_string_pretty(&sort_keys(schema))? + "\n";
+        fs::write(schema_path(out_dir, name), content)?;
+    }
+    Ok(())
+}
+
+/// Keeps the written files stable regardless of map ordering
+fn sort_keys(value: Value) -> Value {
+    match value {
+        Value::Object(object) => {
+            let mut fields = object.into_iter().collect::<Vec<_>>();
+            fields.sort_by(|a, b| a.0.cmp(&b.0));
+            Value::Object(fields.into_iter().map(|(k, v)| (k, sort_keys(v))).collect())
+        }
+        Value::Array(list) => Value::Array(list.into_iter().map(sort_keys).collect()),
+        value => value,
+    }
+}
+
+/// Patches what schemars cannot express from the loader structs alone
+fn annotate(schema: &mut Value) {
+    match schema {
+        Value::Object(object) => {
+            // integer widths only come with a lower bound
+            let maximum = match object.get("format").and_then(Value::as_str) {
+                Some("uint8") => Some(u8::MAX as u64),
+                Some("uint16") => Some(u16::MAX as u64),
+                _ => None,
+            };
+            if let Some(maximum) = maximum {
+                object.insert("maximum".to_owned(), maximum.into());
+            }
+            // serde also accepts unit variants written as `{ "barrier": null }`
+            if let Some(Value::Array(variants)) = object.get_mut("oneOf") {
+                let unit_variants = variants
+                    .iter()
+                    .filter(|v| v["type"] == "string")
+                    .filter_map(|v| v["enum"].as_array())
+                    .flatten()
+                    .filter_map(Value::as_str)
+                    .map(|name| {
+                        serde_json::json!({
+                            "type": "object",
+                            "required": [name],
+                            "properties": { name: { "type": "null" } },
+                            "additionalProperties": false
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                variants.extend(unit_variants);
+            }
+            object.values_mut().for_each(annotate);
+        }
+        Value::Array(list) => list.iter_mut().for_each(annotate),
+        _ => {}
+    }
+}
+
+/// Entries with `extends` only become complete after templates are resolved
+fn allow_templates(schema: &mut Value) {
+    let Some(Value::Object(properties)) = schema.get_mut("properties") else {
+        return;
+    };
+    for property in properties.values_mut() {
+        if let Some(items) = property.get_mut("items") {
+            *items = serde_json::json!({
+                "if": { "required": [EXTENDS_KEY] },
+                "then": { "type": "object" },
+                "else": items.take()
+            });
+        }
+    }
+    properties.insert(
+        TEMPLATES_KEY.to_owned(),
+        serde_json::json!({
+            "type": "object",
+            "additionalProperties": { "type": "object" }
+        }),
+    );
+}
+
+#[test]
+fn test_schemas_up_to_date() {
+    let dir = Path::new("./schemas");
+    for (name, schema) in pool_schemas() {
+        let path = schema_path(dir, name);
+        let content = fs::read_to_string(&path).unwrap_or_default();
+        let stored: Value = serde_json::from_str(&content).unwrap_or_default();
+        assert_eq!(
+            stored,
+            schema,
+            "{} is stale, regenerate it with `cargo run -- schema`",
+            path.display()
+        );
+    }
+}
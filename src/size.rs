@@ -0,0 +1,147 @@
+use eyre::{eyre, Result, WrapErr};
+use molecule::prelude::Reader;
+use serde::{Deserialize, Serialize};
+use spore_warriors_generated as generated;
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::loader::{visit_pools, PoolVisitor, ResourcePoolKind};
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct EntrySize {
+    pub id: u16,
+    pub size: usize,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct PoolSize {
+    pub name: String,
+    pub size: usize,
+    pub entries: Vec<EntrySize>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct SizeReport {
+    pub total: usize,
+    pub pools: Vec<PoolSize>,
+}
+
+struct SizeVisitor<'r> {
+    reader: generated::ResourcePoolReader<'r>,
+    pools: Vec<PoolSize>,
+}
+
+impl PoolVisitor for SizeVisitor<'_> {
+    type Error = Infallible;
+
+    fn visit<P: ResourcePoolKind>(&mut self) -> Result<(), Infallible> {
+        let mut entries = P::entry_slices(self.reader)
+            .into_iter()
+            .map(|(id, data)| EntrySize {
+                id,
+                size: data.len(),
+            })
+            .collect::<Vec<_>>();
+        entries.sort_by(|a, b| b.size.cmp(&a.size).then(a.id.cmp(&b.id)));
+        self.pools.push(PoolSize {
+            name: P::FIELD_NAME.to_owned(),
+            size: P::pool_slice(self.reader).len(),
+            entries,
+        });
+        Ok(())
+    }
+}
+
+impl SizeReport {
+    pub fn new(binary: &[u8]) -> Result<Self> {
+        let reader = generated::ResourcePoolReader::from_slice(binary)
+            .map_err(|err| eyre!("invalid resource binary: {err}"))?;
+        let mut visitor = SizeVisitor {
+            reader,
+            pools: vec![],
+        };
+        let Ok(()) = visit_pools(&mut visitor);
+        Ok(Self {
+            total: binary.len(),
+            pools: visitor.pools,
+        })
+    }
+}
+
+impl fmt::Display for SizeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "total: {} bytes", self.total)?;
+        for pool in &self.pools {
+            writeln!(
+                f,
+                "{}: {} bytes, {} entries",
+                pool.name,
+                pool.size,
+                pool.entries.len()
+            )?;
+            for entry in &pool.entries {
+                writeln!(f, "  {}: {} bytes", entry.id, entry.size)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Byte limits for the generated binary, `pools` is keyed by `ResourcePool` field name
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct SizeBudget {
+    pub total: Option<usize>,
+    #[serde(default)]
+    pub pools: BTreeMap<String, usize>,
+}
+
+impl SizeBudget {
+    pub fn parse_from(path: &Path) -> Result<Self> {
+        let raw_budget =
+            fs::read_to_string(path).wrap_err_with(|| format!("read {}", path.display()))?;
+        Ok(serde_json::from_str(&raw_budget)?)
+    }
+
+    pub fn check(&self, report: &SizeReport) -> Result<()> {
+        let mut exceeded = vec![];
+        if let Some(total) = self.total {
+            if report.total > total {
+                exceeded.push(format!("total: {} > {total} bytes", report.total));
+            }
+        }
+        for (name, limit) in &self.pools {
+            let Some(pool) = report.pools.iter().find(|v| &v.name == name) else {
+                return Err(eyre!("unknown pool `{name}` in size budget"));
+            };
+            if pool.size > *limit {
+                exceeded.push(format!("{name}: {} > {limit} bytes", pool.size));
+            }
+        }
+        if !exceeded.is_empty() {
+            return Err(eyre!("size budget exceeded:\n{}", exceeded.join("\n")));
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_size_report() {
+    use crate::loader::ResourceSet;
+    let resource_set = ResourceSet::load("./resources".as_ref()).unwrap();
+    let binary = resource_set.into_binary();
+    let report = SizeReport::new(&binary).unwrap();
+    println!("[SIZE] {report}");
+    assert_eq!(report.total, binary.len());
+    assert!(report.pools.iter().map(|v| v.size).sum::<usize>() < report.total);
+
+    let mut budget = SizeBudget {
+        total: Some(report.total),
+        ..Default::default()
+    };
+    budget.check(&report).unwrap();
+    budget.pools.insert("scene_pool".to_owned(), 1);
+    assert!(budget.check(&report).is_err());
+}
@@ -0,0 +1,160 @@
+use eyre::{eyre, Result, WrapErr};
+use molecule::prelude::{Builder, Entity, Reader};
+use serde::{Deserialize, Serialize};
+use spore_warriors_generated as generated;
+use std::convert::Infallible;
+use std::fs;
+use std::mem;
+use std::path::Path;
+
+use crate::loader::{visit_pools, PoolVisitor, ResourcePoolKind};
+use crate::manifest::hash_hex;
+
+pub const CELL_INDEX_FILE: &str = "index.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CellIndexEntry {
+    pub name: String,
+    pub molecule_type: String,
+    pub file: String,
+    pub offset: usize,
+    pub size: usize,
+    pub count: usize,
+    pub hash: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CellIndex {
+    pub hash: String,
+    pub size: usize,
+    pub cells: Vec<CellIndexEntry>,
+}
+
+pub struct Cell {
+    pub entry: CellIndexEntry,
+    pub data: Vec<u8>,
+}
+
+struct SplitVisitor<'a> {
+    binary: &'a [u8],
+    reader: generated::ResourcePoolReader<'a>,
+    cells: Vec<Cell>,
+}
+
+impl PoolVisitor for SplitVisitor<'_> {
+    type Error = Infallible;
+
+    fn visit<P: ResourcePoolKind>(&mut self) -> Result<(), Infallible> {
+        let pool = P::pool_slice(self.reader);
+        // every field of the `ResourcePool` table is a subslice of the whole binary
+        let offset = pool.as_ptr() as usize - self.binary.as_ptr() as usize;
+        let data = pool.to_vec();
+        self.cells.push(Cell {
+            entry: CellIndexEntry {
+                name: P::FIELD_NAME.to_owned(),
+                molecule_type: <P::Molecule as Entity>::NAME.to_owned(),
+                file: format!("{}.bin", P::FIELD_NAME),
+                offset,
+                size: data.len(),
+                count: P::entry_slices(self.reader).len(),
+                hash: hash_hex(&data),
+            },
+            data,
+        });
+        Ok(())
+    }
+}
+
+/// Splits a `ResourcePool` binary into one cell per pool vector
+pub fn split_resource_binary(binary: &[u8]) -> Result<(CellIndex, Vec<Cell>)> {
+    let reader = generated::ResourcePoolReader::from_slice(binary)
+        .map_err(|err| eyre!("invalid resource binary: {err}"))?;
+    let mut visitor = SplitVisitor {
+        binary,
+        reader,
+        cells: vec![],
+    };
+    let Ok(()) = visit_pools(&mut visitor);
+    let cells = visitor.cells;
+    let index = CellIndex {
+        hash: hash_hex(binary),
+        size: binary.len(),
+        cells: cells.iter().map(|v| v.entry.clone()).collect(),
+    };
+    Ok((index, cells))
+}
+
+pub fn write_cells(binary: &[u8], out_dir: &Path) -> Result<CellIndex> {
+    let (index, cells) = split_resource_binary(binary)?;
+    fs::create_dir_all(out_dir)?;
+    for cell in cells {
+        let path = out_dir.join(&cell.entry.file);
+        fs::write(&path, cell.data).wrap_err_with(|| format!("write {}", path.display()))?;
+    }
+    fs::write(
+        out_dir.join(CELL_INDEX_FILE),
+        serde_json::to_string_pretty(&index)?,
+    )?;
+    Ok(index)
+}
+
+struct JoinVisitor<'a> {
+    dir: &'a Path,
+    index: &'a CellIndex,
+    builder: generated::ResourcePoolBuilder,
+}
+
+impl PoolVisitor for JoinVisitor<'_> {
+    type Error = eyre::Report;
+
+    fn visit<P: ResourcePoolKind>(&mut self) -> Result<()> {
+        let name = P::FIELD_NAME;
+        let entry = self
+            .index
+            .cells
+            .iter()
+            .find(|v| v.name == name)
+            .ok_or_else(|| eyre!("cell `{name}` is missing from index"))?;
+        let data = fs::read(self.dir.join(&entry.file))
+            .wrap_err_with(|| format!("read cell {}", entry.file))?;
+        if hash_hex(&data) != entry.hash {
+            return Err(eyre!("cell `{name}` does not match its indexed hash"));
+        }
+        let molecule = P::molecule_from_slice(&data).wrap_err_with(|| format!("cell `{name}`"))?;
+        self.builder = P::set_molecule(mem::take(&mut self.builder), molecule);
+        Ok(())
+    }
+}
+
+/// Reassembles the `ResourcePool` binary from the cells written by `write_cells`
+pub fn join_cells(dir: &Path) -> Result<Vec<u8>> {
+    let raw_index = fs::read_to_string(dir.join(CELL_INDEX_FILE))?;
+    let index: CellIndex = serde_json::from_str(&raw_index)?;
+    let mut visitor = JoinVisitor {
+        dir,
+        index: &index,
+        builder: generated::ResourcePool::new_builder(),
+    };
+    visit_pools(&mut visitor)?;
+    let binary = visitor.builder.build().as_bytes().to_vec();
+    if hash_hex(&binary) != index.hash {
+        return Err(eyre!("joined cells do not match the indexed resource hash"));
+    }
+    Ok(binary)
+}
+
+#[test]
+fn test_split_and_join_cells() {
+    use crate::loader::ResourceSet;
+    let binary = ResourceSet::load("./resources".as_ref())
+        .unwrap()
+        .into_binary();
+    let (index, cells) = split_resource_binary(&binary).unwrap();
+    for cell in &cells {
+        let entry = &cell.entry;
+        assert_eq!(&binary[entry.offset..entry.offset + entry.size], cell.data);
+    }
+    let out_dir = std::env::temp_dir().join("spore-warriors-cells");
+    assert_eq!(write_cells(&binary, &out_dir).unwrap(), index);
+    assert_eq!(join_cells(&out_dir).unwrap(), binary);
+}
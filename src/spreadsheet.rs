@@ -0,0 +1,208 @@
+use eyre::{eyre, Result, WrapErr};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::loader::{visit_pools, PoolVisitor, ResourcePoolKind, ResourceSet};
+
+/// Pools simple enough to be edited as one row per entry
+pub const FLAT_POOLS: [&str; 4] = ["cards", "items", "enemies", "warriors"];
+
+const LIST_SUFFIX: &str = "[]";
+const LIST_SEPARATOR: char = ';';
+
+fn flatten(prefix: &str, value: &Value, row: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(fields) => {
+            for (key, field) in fields {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                flatten(&path, field, row);
+            }
+        }
+        Value::Array(list) => {
+            let cells = list.iter().map(cell).collect::<Vec<_>>();
+            row.push((
+                format!("{prefix}{LIST_SUFFIX}"),
+                cells.join(&LIST_SEPARATOR.to_string()),
+            ));
+        }
+        _ => row.push((prefix.to_owned(), cell(value))),
+    }
+}
+
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(v) => v.clone(),
+        _ => value.to_string(),
+    }
+}
+
+fn parse_cell(raw: &str) -> Value {
+    serde_json::from_str::<Value>(raw)
+        .ok()
+        .filter(|v| v.is_number() || v.is_boolean())
+        .unwrap_or_else(|| Value::String(raw.to_owned()))
+}
+
+fn insert_path(object: &mut Map<String, Value>, path: &[&str], value: Value) {
+    match path {
+        [] => {}
+        [key] => {
+            object.insert(key.to_string(), value);
+        }
+        [key, rest @ ..] => {
+            let child = object
+                .entry(key.to_string())
+                .or_insert_with(|| Value::Object(Map::new()));
+            if let Value::Object(child) = child {
+                insert_path(child, rest, value);
+            }
+        }
+    }
+}
+
+/// Writes one row per entry, nested fields become `a.b` columns and lists become
+/// `;`-separated `field[]` columns
+pub fn export_csv<E: Serialize>(entries: &[E]) -> Result<String> {
+    let rows = entries
+        .iter()
+        .map(|entry| {
+            let mut row = vec![];
+            flatten("", &serde_json::to_value(entry)?, &mut row);
+            Ok(row)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let mut header: Vec<String> = vec![];
+    for (column, _) in rows.iter().flatten() {
+        if !header.contains(column) {
+            header.push(column.clone());
+        }
+    }
+    let mut writer = csv::Writer::from_writer(vec![]);
+    writer.write_record(&header)?;
+    for row in rows {
+        writer.write_record(header.iter().map(|column| {
+            row.iter()
+                .find(|(v, _)| v == column)
+                .map(|(_, v)| v.as_str())
+                .unwrap_or_default()
+        }))?;
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+/// Reads rows written by `export_csv`, empty scalar cells fall back to the field default
+pub fn import_csv<E: DeserializeOwned>(raw: &str) -> Result<Vec<E>> {
+    let mut reader = csv::Reader::from_reader(raw.as_bytes());
+    let header = reader.headers()?.clone();
+    let mut entries = vec![];
+    for (line, record) in reader.records().enumerate() {
+        let record = record?;
+        let mut object = Map::new();
+        for (column, raw_cell) in header.iter().zip(record.iter()) {
+            let raw_cell = raw_cell.trim();
+            let (path, value) = match column.strip_suffix(LIST_SUFFIX) {
+                Some(path) => {
+                    let list = raw_cell
+                        .split(LIST_SEPARATOR)
+                        .map(str::trim)
+                        .filter(|v| !v.is_empty())
+                        .map(parse_cell)
+                        .collect();
+                    (path, Value::Array(list))
+                }
+                None if raw_cell.is_empty() => continue,
+                None => (column, parse_cell(raw_cell)),
+            };
+            insert_path(&mut object, &path.split('.').collect::<Vec<_>>(), value);
+        }
+        let id = object.get("id").map(cell).unwrap_or_default();
+        let entry = serde_json::from_value(Value::Object(object))
+            .wrap_err_with(|| format!("row {} (id {id})", line + 2))?;
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+enum SheetTask<'a> {
+    Export(&'a ResourceSet),
+    Import(&'a str),
+}
+
+struct SheetVisitor<'a> {
+    name: &'a str,
+    task: SheetTask<'a>,
+    csv: Option<String>,
+    document: Option<Value>,
+}
+
+impl PoolVisitor for SheetVisitor<'_> {
+    type Error = eyre::Report;
+
+    fn visit<P: ResourcePoolKind>(&mut self) -> Result<()> {
+        if P::FILE_NAME != self.name || !FLAT_POOLS.contains(&self.name) {
+            return Ok(());
+        }
+        match self.task {
+            SheetTask::Export(resource_set) => {
+                self.csv = Some(export_csv(P::of(resource_set).entries())?);
+            }
+            SheetTask::Import(raw) => {
+                let mut pool = P::default();
+                *pool.entries_mut() = import_csv(raw)?;
+                self.document = Some(serde_json::to_value(pool)?);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn visit_sheet<'a>(name: &'a str, task: SheetTask<'a>) -> Result<SheetVisitor<'a>> {
+    if !FLAT_POOLS.contains(&name) {
+        return Err(eyre!(
+            "`{name}` is not one of the flat pools {FLAT_POOLS:?}"
+        ));
+    }
+    let mut visitor = SheetVisitor {
+        name,
+        task,
+        csv: None,
+        document: None,
+    };
+    visit_pools(&mut visitor)?;
+    Ok(visitor)
+}
+
+pub fn export_pool(resource_set: &ResourceSet, name: &str) -> Result<String> {
+    let visitor = visit_sheet(name, SheetTask::Export(resource_set))?;
+    visitor.csv.ok_or_else(|| eyre!("unknown pool `{name}`"))
+}
+
+/// Converts a sheet back into the pool document written to `<name>.json`
+pub fn import_pool(name: &str, raw: &str) -> Result<Value> {
+    let visitor = visit_sheet(name, SheetTask::Import(raw))?;
+    visitor
+        .document
+        .ok_or_else(|| eyre!("unknown pool `{name}`"))
+}
+
+#[test]
+fn test_csv_roundtrip() {
+    let resource_set = ResourceSet::load("./resources".as_ref()).unwrap();
+    let raw_cards = export_pool(&resource_set, "cards").unwrap();
+    println!("[CSV] cards:\n{raw_cards}");
+    assert!(raw_cards.starts_with("id,class,power_cost,price.min,price.max,system_pool[]\n"));
+    let cards: crate::loader::CardPool =
+        serde_json::from_value(import_pool("cards", &raw_cards).unwrap()).unwrap();
+    assert_eq!(cards, resource_set.card_pool);
+
+    let raw_enemies = export_pool(&resource_set, "enemies").unwrap();
+    let enemies: Vec<crate::loader::Enemy> = import_csv(&raw_enemies).unwrap();
+    assert_eq!(enemies, resource_set.enemy_pool.enemy_pool);
+    assert!(export_pool(&resource_set, "scenes").is_err());
+}
@@ -0,0 +1,237 @@
+use eyre::{eyre, Result, WrapErr};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+use crate::loader::{parse_file, DurationTrigger, ResourceFormat, ResourceSet, System, Value};
+use crate::locale::{LocaleTable, LocalizedText};
+
+/// Subdirectory of the resource directory holding one `<locale>.json` template set per locale
+pub const TEXTS_DIR: &str = "texts";
+
+/// How one locale phrases systems, e.g. `"Deal {0} damage{duration}"` for a damage system
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TextTemplates {
+    /// Sentence per `system_id`, `{0}`, `{1}`, ... stand for the rendered args and
+    /// `{duration}` for the duration phrase, which is appended when not placed
+    pub systems: BTreeMap<u16, String>,
+    /// Duration phrase per trigger name or code, `{count}` stands for the duration count
+    #[serde(default)]
+    pub durations: BTreeMap<DurationTrigger, String>,
+    /// Rendering of `Value::Random`, with `{min}` and `{max}`
+    #[serde(default = "default_range")]
+    pub range: String,
+    /// Joins the sentences of a `system_pool`
+    #[serde(default = "default_separator")]
+    pub separator: String,
+}
+
+fn default_range() -> String {
+    "{min}–{max}".to_owned()
+}
+
+fn default_separator() -> String {
+    ". ".to_owned()
+}
+
+impl TextTemplates {
+    /// Loads every template set under `<dir>/texts`, keyed by locale
+    pub fn load(dir: &Path) -> Result<BTreeMap<String, Self>> {
+        let mut locales = BTreeMap::new();
+        let dir = dir.join(TEXTS_DIR);
+        if !dir.is_dir() {
+            return Ok(locales);
+        }
+        let mut files = fs::read_dir(&dir)?
+            .map(|v| Ok(v?.path()))
+            .collect::<Result<Vec<_>>>()?;
+        files.sort();
+        for path in files {
+            if ResourceFormat::from_path(&path).is_err() {
+                continue;
+            }
+            let locale = path
+                .file_stem()
+                .and_then(|v| v.to_str())
+                .unwrap_or_default()
+                .to_owned();
+            let templates = parse_file(&path).wrap_err_with(|| format!("load texts {locale}"))?;
+            locales.insert(locale, templates);
+        }
+        Ok(locales)
+    }
+
+    pub fn render_value(&self, value: &Value) -> String {
+        match value {
+            Value::Number(v) => v.to_string(),
+            Value::Random(v) if v.min == v.max => v.min.to_string(),
+            Value::Random(v) => self
+                .range
+                .replace("{min}", &v.min.to_string())
+                .replace("{max}", &v.max.to_string()),
+        }
+    }
+
+    pub fn render_system(&self, system: &System) -> Result<String> {
+        let template = self
+            .systems
+            .get(&system.system_id)
+            .ok_or_else(|| eyre!("no template for system_id {}", system.system_id))?;
+        let mut text = template.clone();
+        for (i, arg) in system.args.iter().enumerate() {
+            text = text.replace(&format!("{{{i}}}"), &self.render_value(arg));
+        }
+        let duration = match &system.duration {
+            Some(duration) => {
+                let phrase = self.durations.get(&duration.trigger).ok_or_else(|| {
+                    eyre!("no template for duration trigger {}", duration.trigger)
+                })?;
+                phrase.replace("{count}", &duration.count.to_string())
+            }
+            None => String::new(),
+        };
+        if text.contains("{duration}") {
+            text = text.replace("{duration}", &duration);
+        } else if !duration.is_empty() {
+            text = format!("{text} {duration}");
+        }
+        if let Some(start) = text.find('{').filter(|i| text[*i..].contains('}')) {
+            let end = start + text[start..].find('}').unwrap_or_default();
+            return Err(eyre!(
+                "template for system_id {} uses {} but the system has {} args",
+                system.system_id,
+                &text[start..=end],
+                system.args.len()
+            ));
+        }
+        Ok(text)
+    }
+}
+
+/// Renders the text of any `system_pool` for every locale
+pub struct TextRenderer<'a> {
+    systems: HashMap<u16, &'a System>,
+    locales: &'a BTreeMap<String, TextTemplates>,
+}
+
+impl<'a> TextRenderer<'a> {
+    pub fn new(
+        resource_set: &'a ResourceSet,
+        locales: &'a BTreeMap<String, TextTemplates>,
+    ) -> Self {
+        let systems = resource_set.system_pool.system_pool.iter();
+        Self {
+            systems: systems.map(|v| (v.id, v)).collect(),
+            locales,
+        }
+    }
+
+    pub fn render(&self, locale: &str, system_pool: &[u16]) -> Result<String> {
+        let templates = self
+            .locales
+            .get(locale)
+            .ok_or_else(|| eyre!("no text templates for locale {locale}"))?;
+        let sentences = system_pool
+            .iter()
+            .map(|id| {
+                let system = self
+                    .systems
+                    .get(id)
+                    .ok_or_else(|| eyre!("unknown system {id}"))?;
+                templates
+                    .render_system(system)
+                    .wrap_err_with(|| format!("render system {id} in {locale}"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(sentences.join(&templates.separator))
+    }
+
+    /// Card and item descriptions of every locale, shaped like the localization tables
+    pub fn descriptions(
+        &self,
+        resource_set: &ResourceSet,
+    ) -> Result<BTreeMap<String, LocaleTable>> {
+        let cards = resource_set.card_pool.card_pool.iter();
+        let items = resource_set.item_pool.item_pool.iter();
+        let entries = cards
+            .map(|v| ("cards", v.id, &v.system_pool))
+            .chain(items.map(|v| ("items", v.id, &v.system_pool)))
+            .collect::<Vec<_>>();
+        let mut tables = BTreeMap::new();
+        for locale in self.locales.keys() {
+            let mut table = LocaleTable::new();
+            for (pool, id, system_pool) in &entries {
+                let description = self
+                    .render(locale, system_pool)
+                    .wrap_err_with(|| format!("{pool} {id}"))?;
+                let text = LocalizedText {
+                    description,
+                    ..Default::default()
+                };
+                table.entry(pool.to_string()).or_default().insert(*id, text);
+            }
+            tables.insert(locale.clone(), table);
+        }
+        Ok(tables)
+    }
+}
+
+#[test]
+fn test_render_card_text() {
+    let resource_set = ResourceSet::load("./resources".as_ref()).unwrap();
+    let templates = |systems: &[(u16, &str)], turns: &str| TextTemplates {
+        systems: systems.iter().map(|(k, v)| (*k, v.to_string())).collect(),
+        durations: BTreeMap::from([(DurationTrigger::Trigger0, turns.to_owned())]),
+        range: default_range(),
+        separator: default_separator(),
+    };
+    let locales = BTreeMap::from([
+        (
+            "en".to_owned(),
+            templates(
+                &[(0, "Deal {0} damage"), (1, "Gain {0} to {1} armor")],
+                "for {count} turns",
+            ),
+        ),
+        (
+            "zh-CN".to_owned(),
+            templates(
+                &[(0, "造成 {0} 点伤害"), (1, "{duration}获得 {0}–{1} 点护甲")],
+                "{count} 回合内",
+            ),
+        ),
+    ]);
+    let mut resource_set = resource_set;
+    resource_set.system_pool.system_pool[0].args =
+        vec![Value::Random(crate::loader::Random { min: 5, max: 10 })];
+    resource_set.system_pool.system_pool[2].duration = Some(crate::loader::Duration {
+        trigger: DurationTrigger::Trigger0,
+        count: 3,
+    });
+    let renderer = TextRenderer::new(&resource_set, &locales);
+    assert_eq!(
+        renderer.render("en", &[1, 3]).unwrap(),
+        "Deal 5–10 damage. Gain 5 to 5 armor for 3 turns"
+    );
+    assert_eq!(
+        renderer.render("zh-CN", &[3]).unwrap(),
+        "3 回合内获得 5–5 点护甲"
+    );
+    assert!(renderer.render("fr", &[1]).is_err());
+
+    let descriptions = renderer.descriptions(&resource_set).unwrap();
+    assert_eq!(
+        descriptions["en"]["cards"][&102].description,
+        "Gain 5 to 5 armor for 3 turns"
+    );
+    assert_eq!(descriptions["en"]["items"].len(), 3);
+
+    let parsed: TextTemplates =
+        serde_json::from_str(r#"{ "systems": {}, "durations": { "trigger_1": "a", "2": "b" } }"#)
+            .unwrap();
+    assert_eq!(
+        parsed.durations.keys().collect::<Vec<_>>(),
+        [&DurationTrigger::Trigger1, &DurationTrigger::Trigger2]
+    );
+}
@@ -0,0 +1,445 @@
+use eyre::{eyre, Result};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::convert::Infallible;
+use std::fmt;
+use std::path::Path;
+
+use crate::limits::SoftLimits;
+use crate::loader::{
+    visit_pools, CardClass, NodeInstance, Package, PoolVisitor, ResourcePoolKind, ResourceSet,
+    SystemDeclarations, SYSTEM_IDS_FILE,
+};
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ValidationError {
+    pub pool: String,
+    pub id: u16,
+    pub field: String,
+    pub message: String,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Default)]
+pub struct ValidationReport {
+    pub errors: Vec<ValidationError>,
+    /// Checks that could not run, reported without failing `into_result`
+    pub warnings: Vec<ValidationError>,
+}
+
+impl ValidationReport {
+    /// Whether there are no errors, warnings are not counted
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    pub fn error(&mut self, pool: &str, id: u16, field: &str, message: impl Into<String>) {
+        self.errors.push(ValidationError {
+            pool: pool.to_owned(),
+            id,
+            field: field.to_owned(),
+            message: message.into(),
+        });
+    }
+
+    pub fn warning(&mut self, pool: &str, id: u16, field: &str, message: impl Into<String>) {
+        self.warnings.push(ValidationError {
+            pool: pool.to_owned(),
+            id,
+            field: field.to_owned(),
+            message: message.into(),
+        });
+    }
+
+    pub fn into_result(self) -> Result<()> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(eyre!("{} validation errors:\n{self}", self.errors.len()))
+        }
+    }
+}
+
+struct PoolIds<'a> {
+    resource_set: &'a ResourceSet,
+    ids: BTreeMap<&'static str, BTreeSet<u16>>,
+}
+
+impl PoolVisitor for PoolIds<'_> {
+    type Error = Infallible;
+
+    fn visit<P: ResourcePoolKind>(&mut self) -> Result<(), Infallible> {
+        let ids = P::of(self.resource_set).entries().iter().map(P::id);
+        self.ids.insert(P::FILE_NAME, ids.collect());
+        Ok(())
+    }
+}
+
+struct Validator {
+    ids: BTreeMap<&'static str, BTreeSet<u16>>,
+    report: ValidationReport,
+}
+
+impl Validator {
+    fn new(resource_set: &ResourceSet) -> Self {
+        let mut ids = PoolIds {
+            resource_set,
+            ids: BTreeMap::new(),
+        };
+        let Ok(()) = visit_pools(&mut ids);
+        Self {
+            ids: ids.ids,
+            report: ValidationReport::default(),
+        }
+    }
+
+    fn references(&mut self, pool: &str, id: u16, field: &str, target: &str, refs: &[u16]) {
+        for reference in refs {
+            if !self.ids[target].contains(reference) {
+                self.report.error(
+                    pool,
+                    id,
+                    field,
+                    format!("references unknown {target} id {reference}"),
+                );
+            }
+        }
+    }
+
+    /// Closed code enums only hold `Unknown` when decoded from a binary
+    fn code(&mut self, pool: &str, id: u16, field: &str, known: bool, code: impl fmt::Display) {
+        if !known {
+            self.report.error(pool, id, field, code.to_string());
+        }
+    }
+
+    fn package(&mut self, id: u16, field: &str, package: &Package) {
+        self.references(
+            "loots",
+            id,
+            &format!("{field}.item_pool"),
+            "items",
+            &package.item_pool,
+        );
+    }
+}
+
+/// Checks every cross-pool id reference of a resource set
+pub fn validate(resource_set: &ResourceSet) -> ValidationReport {
+    let mut validator = Validator::new(resource_set);
+    for action in &resource_set.action_pool.action_pool {
+        validator.references(
+            "actions",
+            action.id,
+            "system_pool",
+            "systems",
+            &action.system_pool,
+        );
+    }
+    for system in &resource_set.system_pool.system_pool {
+        let target = system.target_type;
+        validator.code(
+            "systems",
+            system.id,
+            "target_type",
+            target.is_known(),
+            target,
+        );
+        if let Some(duration) = &system.duration {
+            let trigger = duration.trigger;
+            let field = "duration.trigger";
+            validator.code("systems", system.id, field, trigger.is_known(), trigger);
+        }
+        if system.duration.as_ref().is_some_and(|v| v.count == 0) {
+            let message = "must be greater than 0";
+            validator
+                .report
+                .error("systems", system.id, "duration.count", message);
+        }
+    }
+    for card in &resource_set.card_pool.card_pool {
+        validator.code("cards", card.id, "class", card.class.is_known(), card.class);
+        validator.references(
+            "cards",
+            card.id,
+            "system_pool",
+            "systems",
+            &card.system_pool,
+        );
+    }
+    for item in &resource_set.item_pool.item_pool {
+        validator.code("items", item.id, "class", item.class.is_known(), item.class);
+        validator.references(
+            "items",
+            item.id,
+            "system_pool",
+            "systems",
+            &item.system_pool,
+        );
+    }
+    for enemy in &resource_set.enemy_pool.enemy_pool {
+        validator.code(
+            "enemies",
+            enemy.id,
+            "rank",
+            enemy.rank.is_known(),
+            enemy.rank,
+        );
+        validator.references("enemies", enemy.id, "loot_pool", "loots", &enemy.loot_pool);
+        validator.references(
+            "enemies",
+            enemy.id,
+            "action_strategy.action_pool",
+            "actions",
+            &enemy.action_strategy.action_pool,
+        );
+    }
+    for loot in &resource_set.loot_pool.loot_pool {
+        validator.package(loot.id, "card_pool", &loot.card_pool);
+        if let Some(package) = &loot.props_pool {
+            validator.package(loot.id, "props_pool", package);
+        }
+        if let Some(package) = &loot.equipment_pool {
+            validator.package(loot.id, "equipment_pool", package);
+        }
+    }
+    for scene in &resource_set.scene_pool.scene_pool {
+        let fixed_nodes = scene
+            .fixed_nodes
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (format!("fixed_nodes[{i}].node"), &v.node));
+        let partition_nodes = scene
+            .partition_list
+            .iter()
+            .enumerate()
+            .flat_map(|(i, partition)| {
+                partition
+                    .node_pool
+                    .iter()
+                    .enumerate()
+                    .map(move |(j, v)| (format!("partition_list[{i}].node_pool[{j}]"), v))
+            });
+        for (field, node) in fixed_nodes.chain(partition_nodes) {
+            let (name, target, refs) = match &node.instance {
+                NodeInstance::Enemy(v) => ("enemy_pool", "enemies", v.enemy_pool.clone()),
+                NodeInstance::TreasureChest(v) => ("item_pool", "items", v.item_pool.clone()),
+                NodeInstance::ItemMerchant(v) => ("item_pool", "items", v.item_pool.clone()),
+                NodeInstance::CardMerchant(v) => ("card_pool", "cards", v.card_pool.clone()),
+                NodeInstance::Unknown(v) => ("system_pool", "systems", v.system_pool.clone()),
+                NodeInstance::Campsite(v) => ("campsite", "systems", vec![*v]),
+                _ => continue,
+            };
+            let field = format!("{field}.instance.{name}");
+            validator.references("scenes", scene.id, &field, target, &refs);
+        }
+    }
+    for warrior in &resource_set.warrior_pool.warrior_pool {
+        let id = warrior.id;
+        validator.references(
+            "warriors",
+            id,
+            "special_cards",
+            "cards",
+            &warrior.special_cards,
+        );
+        validator.references("warriors", id, "deck_status", "cards", &warrior.deck_status);
+        validator.references(
+            "warriors",
+            id,
+            "package_status",
+            "items",
+            &warrior.package_status,
+        );
+    }
+    validate_loadouts(resource_set, &mut validator.report);
+    validator.report
+}
+
+/// Starting loadout rules on top of the reference checks: special cards come from the deck
+/// unless they are `special` class cards, the first draw can be filled and at least one deck
+/// card is playable with the warrior's power
+fn validate_loadouts(resource_set: &ResourceSet, report: &mut ValidationReport) {
+    let card_pool = &resource_set.card_pool;
+    for warrior in &resource_set.warrior_pool.warrior_pool {
+        let id = warrior.id;
+        for card_id in &warrior.special_cards {
+            let Some(card) = card_pool.find(*card_id) else {
+                continue;
+            };
+            if card.class != CardClass::Special && !warrior.deck_status.contains(card_id) {
+                let message = format!("{card_id} is neither in deck_status nor a special card");
+                report.error("warriors", id, "special_cards", message);
+            }
+        }
+        if warrior.deck_status.len() < warrior.draw_count as usize {
+            let message = format!(
+                "holds {} cards, fewer than draw_count {}",
+                warrior.deck_status.len(),
+                warrior.draw_count
+            );
+            report.error("warriors", id, "deck_status", message);
+        }
+        let costs = warrior
+            .deck_status
+            .iter()
+            .filter_map(|v| card_pool.find(*v))
+            .map(|v| v.power_cost)
+            .collect::<Vec<_>>();
+        if !costs.is_empty() && costs.iter().all(|v| *v > warrior.power) {
+            let message = format!(
+                "{} cannot afford any deck card, the cheapest costs {}",
+                warrior.power,
+                costs.iter().min().unwrap()
+            );
+            report.error("warriors", id, "power", message);
+        }
+    }
+}
+
+/// Checks systems against the declared `system_id`s: every id must be declared and only
+/// duration-capable ones may carry a `Duration`
+pub fn validate_systems(
+    resource_set: &ResourceSet,
+    declarations: &SystemDeclarations,
+    report: &mut ValidationReport,
+) {
+    for system in &resource_set.system_pool.system_pool {
+        let Some(declaration) = declarations.get(system.system_id) else {
+            let message = format!("{} is not declared in {SYSTEM_IDS_FILE}", system.system_id);
+            report.error("systems", system.id, "system", message);
+            continue;
+        };
+        if system.duration.is_some() && !declaration.duration {
+            let message = format!("is not supported by system_id {}", system.system_id);
+            report.error("systems", system.id, "duration", message);
+        }
+    }
+}
+
+/// Every check that applies to the resource directory `resource_set` was loaded from
+pub fn validate_dir(dir: &Path, resource_set: &ResourceSet) -> Result<ValidationReport> {
+    let mut report = validate(resource_set);
+    match SystemDeclarations::load(dir)? {
+        Some(declarations) => validate_systems(resource_set, &declarations, &mut report),
+        // the contract ships no declarations yet, so their absence only skips the check
+        None => {
+            for system in &resource_set.system_pool.system_pool {
+                if system.duration.is_some() {
+                    let message = format!("cannot be checked, {SYSTEM_IDS_FILE} is missing");
+                    report.warning("systems", system.id, "duration", message);
+                }
+            }
+        }
+    }
+    if let Some(limits) = SoftLimits::load(dir)? {
+        limits.check(resource_set, &mut report)?;
+    }
+    Ok(report)
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {}: {} {}",
+            self.pool, self.id, self.field, self.message
+        )
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.errors.iter().try_for_each(|v| writeln!(f, "{v}"))?;
+        self.warnings
+            .iter()
+            .try_for_each(|v| writeln!(f, "warning: {v}"))
+    }
+}
+
+#[test]
+fn test_validate_references() {
+    let mut resource_set = ResourceSet::load("./resources".as_ref()).unwrap();
+    assert!(validate(&resource_set).is_empty());
+    resource_set.enemy_pool.enemy_pool[1].loot_pool.push(2009);
+    resource_set.enemy_pool.enemy_pool[1].rank = crate::loader::EnemyRank::decode(7);
+    resource_set.warrior_pool.warrior_pool[0]
+        .package_status
+        .push(100);
+    assert_eq!(
+        validate(&resource_set).to_string(),
+        "enemies 3001: rank unknown enemy rank 7\n\
+         enemies 3001: loot_pool references unknown loots id 2009\n\
+         warriors 5001: package_status references unknown items id 100\n"
+    );
+}
+
+#[test]
+fn test_validate_durations() {
+    use crate::loader::{Duration, DurationTrigger, SystemDeclaration};
+
+    let mut resource_set = ResourceSet::load("./resources".as_ref()).unwrap();
+    let systems = &mut resource_set.system_pool.system_pool;
+    systems[0].duration = Some(Duration {
+        trigger: DurationTrigger::Trigger1,
+        count: 0,
+    });
+    systems[2].duration = Some(Duration {
+        trigger: DurationTrigger::Trigger2,
+        count: 2,
+    });
+    systems[3].system_id = 9;
+    let declarations = SystemDeclarations {
+        system_ids: vec![
+            SystemDeclaration {
+                id: 0,
+                name: "damage".to_owned(),
+                duration: true,
+            },
+            SystemDeclaration {
+                id: 1,
+                name: "armor".to_owned(),
+                duration: false,
+            },
+        ],
+    };
+    let mut report = validate(&resource_set);
+    validate_systems(&resource_set, &declarations, &mut report);
+    assert_eq!(
+        report.to_string(),
+        "systems 1: duration.count must be greater than 0\n\
+         systems 3: duration is not supported by system_id 1\n\
+         systems 4: system 9 is not declared in system_ids\n"
+    );
+
+    let report = validate_dir("./resources".as_ref(), &resource_set).unwrap();
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(
+        report.warnings[1].to_string(),
+        "systems 3: duration cannot be checked, system_ids is missing"
+    );
+    assert_eq!(report.warnings.len(), 2);
+    assert!(report.into_result().is_err());
+    let mut resource_set = ResourceSet::load("./resources".as_ref()).unwrap();
+    resource_set.system_pool.system_pool[2].duration = Some(Duration {
+        trigger: DurationTrigger::Trigger0,
+        count: 1,
+    });
+    let report = validate_dir("./resources".as_ref(), &resource_set).unwrap();
+    assert_eq!(report.warnings.len(), 1);
+    report.into_result().unwrap();
+}
+
+#[test]
+fn test_validate_loadouts() {
+    let mut resource_set = ResourceSet::load("./resources".as_ref()).unwrap();
+    let warrior = &mut resource_set.warrior_pool.warrior_pool[1];
+    warrior.deck_status = vec![100];
+    warrior.draw_count = 2;
+    warrior.power = 1;
+    assert_eq!(
+        validate(&resource_set).to_string(),
+        "warriors 5002: special_cards 102 is neither in deck_status nor a special card\n\
+         warriors 5002: deck_status holds 1 cards, fewer than draw_count 2\n\
+         warriors 5002: power 1 cannot afford any deck card, the cheapest costs 2\n"
+    );
+}
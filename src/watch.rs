@@ -0,0 +1,70 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::limits::LIMITS_FILE;
+use crate::loader::{find_resource, resource_files, RESOURCE_FILES, SYSTEM_IDS_FILE};
+
+/// Files besides the pools that validation reads, edits to them need a rebuild as well
+pub const WATCHED_CONFIGS: [&str; 2] = [LIMITS_FILE, SYSTEM_IDS_FILE];
+
+type Stamps = Vec<(PathBuf, Option<SystemTime>)>;
+
+/// Polls the modification times of every pool and config file under a resource directory
+pub struct ResourceWatcher {
+    dir: PathBuf,
+    stamps: BTreeMap<&'static str, Stamps>,
+}
+
+impl ResourceWatcher {
+    pub fn new(dir: &Path) -> Self {
+        let mut watcher = Self {
+            dir: dir.to_owned(),
+            stamps: BTreeMap::new(),
+        };
+        watcher.changed_sources();
+        watcher
+    }
+
+    /// Pools and `WATCHED_CONFIGS` with files added, removed or modified since the
+    /// previous call
+    pub fn changed_sources(&mut self) -> Vec<&'static str> {
+        RESOURCE_FILES
+            .into_iter()
+            .chain(WATCHED_CONFIGS)
+            .filter(|name| {
+                let stamps = pool_stamps(&self.dir, name);
+                self.stamps.insert(name, stamps.clone()) != Some(stamps)
+            })
+            .collect()
+    }
+}
+
+fn pool_stamps(dir: &Path, name: &str) -> Stamps {
+    // an ambiguous pool has no stamps, so the rebuild that reports it runs again once fixed
+    find_resource(dir, name)
+        .and_then(|path| resource_files(&path))
+        .unwrap_or_default()
+        .into_iter()
+        .map(|path| {
+            let modified = fs::metadata(&path).and_then(|v| v.modified()).ok();
+            (path, modified)
+        })
+        .collect()
+}
+
+#[test]
+fn test_watch_changed_sources() {
+    let dir = std::env::temp_dir().join("spore-warriors-watch");
+    std::fs::create_dir_all(&dir).unwrap();
+    let _ = std::fs::remove_file(dir.join("cards.json"));
+    let _ = std::fs::remove_file(dir.join("limits.json"));
+    let mut watcher = ResourceWatcher::new(&dir);
+    assert!(watcher.changed_sources().is_empty());
+    std::fs::write(dir.join("cards.json"), r#"{ "cards": [] }"#).unwrap();
+    assert_eq!(watcher.changed_sources(), ["cards"]);
+    assert!(watcher.changed_sources().is_empty());
+    std::fs::write(dir.join("limits.json"), "{}").unwrap();
+    assert_eq!(watcher.changed_sources(), ["limits"]);
+}